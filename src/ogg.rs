@@ -10,6 +10,7 @@ use crate::{
 };
 use ogg::PacketReader;
 use std::{
+    collections::VecDeque,
     error,
     fmt::{self, Debug, Display, Formatter},
     io::prelude::*,
@@ -28,6 +29,12 @@ pub enum OggOpusError {
     /// The Identificaion Header indicated that this Ogg file conforms to an unsupported version of
     /// the specification.
     UnsupportedVersion,
+    /// A decode sample rate was requested that Opus cannot natively decode to.
+    UnsupportedSampleRate,
+    /// A `METADATA_BLOCK_PICTURE` comment was not a validly encoded FLAC picture block.
+    BadPicture,
+    /// Allocating storage for the comment data or vendor string failed.
+    AllocationFailed,
 }
 
 impl Display for OggOpusError {
@@ -37,6 +44,13 @@ impl Display for OggOpusError {
             OggOpusError::BadPaging => "bad ogg paging alignment",
             OggOpusError::BadMagic => "invalid magic number",
             OggOpusError::UnsupportedVersion => "unsupported encapsulation specification version",
+            OggOpusError::UnsupportedSampleRate => {
+                "requested decode sample rate is not one Opus can natively decode to"
+            }
+            OggOpusError::BadPicture => "malformed METADATA_BLOCK_PICTURE comment",
+            OggOpusError::AllocationFailed => {
+                "allocating storage for comment data or the vendor string failed"
+            }
         })
     }
 }
@@ -144,25 +158,35 @@ impl<'a> Iterator for Comments<'a> {
         use byteorder::{ByteOrder, LE};
         use std::str::from_utf8;
 
-        if self.pos < self.comments.len() && self.comments_read < self.comments_num {
+        // Loops rather than returning `None` straight from a malformed entry, so that one bad
+        // comment doesn't look indistinguishable from (and silently swallow) every comment
+        // after it—only a truncated length prefix, which leaves no way to find where the next
+        // entry would even start, ends iteration early.
+        while self.pos < self.comments.len() && self.comments_read < self.comments_num {
             // get comment length
             let cmt_start = self.pos + 4;
             let cmt_len = LE::read_u32(self.comments.get(self.pos..cmt_start)?) as usize;
 
             // bookkeeping
-            // this is located here so that on comment parse failure, calling .next() again returns
+            // this is located here so that on comment parse failure, resuming the loop returns
             // the next comment
             self.pos = cmt_start + cmt_len;
             self.comments_read += 1;
 
-            // parse comment
-            let cmt = from_utf8(self.comments.get(cmt_start..self.pos)?).ok()?;
-            let (name, value) = cmt.split_at(cmt.find('=')?);
-
-            Some((name, &value[1..]))
-        } else {
-            None
+            // parse comment, skipping (not stopping on) a malformed one
+            if let Some(cmt) = self
+                .comments
+                .get(cmt_start..self.pos)
+                .and_then(|bytes| from_utf8(bytes).ok())
+            {
+                if let Some(eq) = cmt.find('=') {
+                    let (name, value) = cmt.split_at(eq);
+                    return Some((name, &value[1..]));
+                }
+            }
         }
+
+        None
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
@@ -188,8 +212,24 @@ impl CommentHeader {
     /// Packet position after which to ignore comments.
     const COMMENTS_IGNORE_LEN: usize = 61_440;
 
-    /// Create a new comment header representation from bytes.
+    /// Create a new comment header representation from bytes, truncating stored comment data
+    /// past [`COMMENTS_IGNORE_LEN`](Self::COMMENTS_IGNORE_LEN) as usual.
     fn new(data: &[u8]) -> Result<Self> {
+        Self::new_impl(data, false)
+    }
+
+    /// Create a new comment header representation from bytes, retaining the full comment data
+    /// up to [`PACKET_LEN_MAX`](Self::PACKET_LEN_MAX) instead of truncating at
+    /// [`COMMENTS_IGNORE_LEN`](Self::COMMENTS_IGNORE_LEN).
+    ///
+    /// Large embedded data—most commonly a `METADATA_BLOCK_PICTURE` cover image—otherwise falls
+    /// past the usual truncation point and is silently unavailable; this opts into keeping it,
+    /// still subject to the same `PACKET_LEN_MAX` denial-of-service check.
+    fn new_retaining_full(data: &[u8]) -> Result<Self> {
+        Self::new_impl(data, true)
+    }
+
+    fn new_impl(data: &[u8], retain_full: bool) -> Result<Self> {
         use byteorder::{ByteOrder, LE};
 
         // Denial-of-Service check
@@ -198,18 +238,34 @@ impl CommentHeader {
         } else if data.get_res(..8)? == Self::MAGIC {
             // only parses the vendor string (for debugging) at initialization
             let comments_start = 12 + LE::read_u32(data.get_res(8..12)?) as usize;
-            let vendor = String::from_utf8_lossy(data.get_res(12..comments_start)?).into_owned();
+            let vendor_lossy = String::from_utf8_lossy(data.get_res(12..comments_start)?);
+
+            // Copying with `vendor_lossy.into_owned()` and `slice.to_owned()` would abort the
+            // process on allocation failure, which a hostile packet can trigger on purpose by legitimately
+            // reaching `PACKET_LEN_MAX` (120 MiB); reserving fallibly instead turns that into a
+            // recoverable error.
+            let mut vendor = String::new();
+            vendor
+                .try_reserve_exact(vendor_lossy.len())
+                .map_err(|_| OggOpusError::AllocationFailed)?;
+            vendor.push_str(&vendor_lossy);
+
             let num_comments = LE::read_u32(data.get_res(comments_start..comments_start + 4)?);
 
             // we still save the comment data so that we can parse it later if necessary.
             // also, some more DOS checks
-            let comments = if data.len() <= Self::COMMENTS_IGNORE_LEN {
+            let comment_data = if retain_full || data.len() <= Self::COMMENTS_IGNORE_LEN {
                 &data[comments_start + 4..]
             } else {
                 &data[comments_start + 4..Self::COMMENTS_IGNORE_LEN]
-            }
-            .to_owned()
-            .into_boxed_slice();
+            };
+
+            let mut comments = Vec::new();
+            comments
+                .try_reserve_exact(comment_data.len())
+                .map_err(|_| OggOpusError::AllocationFailed)?;
+            comments.extend_from_slice(comment_data);
+            let comments = comments.into_boxed_slice();
 
             Ok(CommentHeader {
                 comments,
@@ -235,6 +291,101 @@ impl CommentHeader {
     fn vendor(&self) -> &str {
         &self.vendor[..]
     }
+
+    /// Case-insensitively looks up every value stored under `key` (e.g. `"TITLE"`), per the
+    /// Vorbis comment convention that field names compare ASCII-case-insensitively.
+    fn get_all<'a>(&'a self, key: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.comments()
+            .filter(move |(name, _)| name.eq_ignore_ascii_case(key))
+            .map(|(_, value)| value)
+    }
+
+    /// Case-insensitively looks up the first value stored under `key`.
+    fn get(&self, key: &str) -> Option<&str> {
+        self.get_all(key).next()
+    }
+
+    /// Fallibly clones `s`, so that building up mutated comment data doesn't abort the process
+    /// on allocation failure the same way [`new_impl`](CommentHeader::new_impl) avoids it for
+    /// the initial parse—comment values can be attacker-controlled up to `PACKET_LEN_MAX` via
+    /// [`new_retaining_full`](CommentHeader::new_retaining_full).
+    fn try_to_owned(s: &str) -> Result<String> {
+        let mut owned = String::new();
+        owned
+            .try_reserve_exact(s.len())
+            .map_err(|_| OggOpusError::AllocationFailed)?;
+        owned.push_str(s);
+        Ok(owned)
+    }
+
+    /// Collects every comment not stored under `key` (compared case-insensitively) into an
+    /// owned, fallibly allocated `Vec`.
+    fn collect_except(&self, key: &str) -> Result<Vec<(String, String)>> {
+        let kept = self.comments().filter(|(name, _)| !name.eq_ignore_ascii_case(key));
+        let count = kept.clone().count();
+
+        let mut owned = Vec::new();
+        owned
+            .try_reserve_exact(count)
+            .map_err(|_| OggOpusError::AllocationFailed)?;
+
+        for (name, value) in kept {
+            owned.push((Self::try_to_owned(name)?, Self::try_to_owned(value)?));
+        }
+
+        Ok(owned)
+    }
+
+    /// Serializes `comments` back into this header's raw comment-data encoding, replacing
+    /// whatever was stored before.
+    fn rebuild(&mut self, comments: &[(String, String)]) -> Result<()> {
+        let total_len: usize = comments
+            .iter()
+            .map(|(name, value)| 4 + name.len() + 1 + value.len())
+            .sum();
+
+        let mut bytes = Vec::new();
+        bytes
+            .try_reserve_exact(total_len)
+            .map_err(|_| OggOpusError::AllocationFailed)?;
+
+        for (name, value) in comments {
+            let entry_len = name.len() + 1 + value.len();
+            bytes.extend_from_slice(&(entry_len as u32).to_le_bytes());
+            bytes.extend_from_slice(name.as_bytes());
+            bytes.push(b'=');
+            bytes.extend_from_slice(value.as_bytes());
+        }
+
+        self.comments = bytes.into_boxed_slice();
+        self.comments_num = comments.len() as u32;
+        Ok(())
+    }
+
+    /// Replaces every existing comment stored under `key` (compared case-insensitively) with a
+    /// single `key=value` comment, appending it if `key` wasn't already present.
+    fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        let mut comments = self.collect_except(key)?;
+        comments.try_reserve(1).map_err(|_| OggOpusError::AllocationFailed)?;
+        comments.push((Self::try_to_owned(key)?, Self::try_to_owned(value)?));
+        self.rebuild(&comments)
+    }
+
+    /// Removes every comment stored under `key` (compared case-insensitively), returning how
+    /// many were removed.
+    fn remove(&mut self, key: &str) -> Result<usize> {
+        let removed = self.get_all(key).count();
+        let kept = self.collect_except(key)?;
+        self.rebuild(&kept)?;
+        Ok(removed)
+    }
+
+    /// Decodes every `METADATA_BLOCK_PICTURE` comment into a [`Picture`], silently skipping any
+    /// that aren't a validly base64-encoded FLAC picture block.
+    fn pictures(&self) -> impl Iterator<Item = Picture> + '_ {
+        self.get_all("METADATA_BLOCK_PICTURE")
+            .filter_map(|value| Picture::decode(value).ok())
+    }
 }
 
 impl Debug for CommentHeader {
@@ -250,10 +401,135 @@ impl Debug for CommentHeader {
     }
 }
 
+/// A decoded `METADATA_BLOCK_PICTURE` comment: a FLAC-format picture block, base64-encoded as a
+/// single Vorbis comment value per the [Xiph convention].
+///
+/// [Xiph convention]: https://xiph.org/flac/format.html#metadata_block_picture
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Picture {
+    /// The picture's purpose, per the ID3v2 APIC frame's picture type field (e.g. `3` for
+    /// "Cover (front)", `4` for "Cover (back)").
+    picture_type: u32,
+    mime_type: String,
+    description: String,
+    width: u32,
+    height: u32,
+    depth: u32,
+    /// For indexed-color pictures (e.g. GIF), the number of colors used; `0` otherwise.
+    colors: u32,
+    data: Vec<u8>,
+}
+
+impl Picture {
+    /// Decodes a `METADATA_BLOCK_PICTURE` comment's base64-encoded value into its picture
+    /// block.
+    fn decode(value: &str) -> Result<Picture> {
+        use byteorder::{ByteOrder, BE};
+
+        let block = base64::decode(value).map_err(|_| OggOpusError::BadPicture)?;
+
+        let picture_type = BE::read_u32(block.get_res(0..4)?);
+
+        let mime_len = BE::read_u32(block.get_res(4..8)?) as usize;
+        let mime_end = 8 + mime_len;
+        let mime_type = std::str::from_utf8(block.get_res(8..mime_end)?)
+            .map_err(|_| OggOpusError::BadPicture)?
+            .to_owned();
+
+        let desc_start = mime_end + 4;
+        let desc_len = BE::read_u32(block.get_res(mime_end..desc_start)?) as usize;
+        let desc_end = desc_start + desc_len;
+        let description = std::str::from_utf8(block.get_res(desc_start..desc_end)?)
+            .map_err(|_| OggOpusError::BadPicture)?
+            .to_owned();
+
+        let width = BE::read_u32(block.get_res(desc_end..desc_end + 4)?);
+        let height = BE::read_u32(block.get_res(desc_end + 4..desc_end + 8)?);
+        let depth = BE::read_u32(block.get_res(desc_end + 8..desc_end + 12)?);
+        let colors = BE::read_u32(block.get_res(desc_end + 12..desc_end + 16)?);
+
+        let data_start = desc_end + 20;
+        let data_len = BE::read_u32(block.get_res(desc_end + 16..data_start)?) as usize;
+        let data = block.get_res(data_start..data_start + data_len)?.to_vec();
+
+        Ok(Picture {
+            picture_type,
+            mime_type,
+            description,
+            width,
+            height,
+            depth,
+            colors,
+            data,
+        })
+    }
+
+    /// The picture's purpose, per the ID3v2 APIC frame's picture type field (e.g. `3` for
+    /// "Cover (front)", `4` for "Cover (back)").
+    #[inline]
+    pub fn picture_type(&self) -> u32 {
+        self.picture_type
+    }
+
+    /// The picture's MIME type (e.g. `"image/jpeg"`).
+    #[inline]
+    pub fn mime_type(&self) -> &str {
+        &self.mime_type
+    }
+
+    /// A free-form, human-readable description of the picture.
+    #[inline]
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    /// The picture's width, in pixels.
+    #[inline]
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// The picture's height, in pixels.
+    #[inline]
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// The picture's color depth, in bits per pixel.
+    #[inline]
+    pub fn depth(&self) -> u32 {
+        self.depth
+    }
+
+    /// For indexed-color pictures (e.g. GIF), the number of colors used; `0` otherwise.
+    #[inline]
+    pub fn colors(&self) -> u32 {
+        self.colors
+    }
+
+    /// The raw, still-encoded (e.g. still JPEG/PNG-compressed) image data.
+    #[inline]
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+}
+
 /// A reader for Ogg Opus files and/or streams.
 pub struct OggOpusReader<R: Read + Seek> {
     comments: CommentHeader,
     decoder: Decoder,
+    /// The sample rate the decoder is producing output at, so a seek can rebuild it.
+    decode_sample_rate: u32,
+    /// The channel count the decoder was built with, so a seek can rebuild it.
+    channels: u8,
+    /// The number of samples (at `decode_sample_rate`) of `pre_skip` still to be discarded from
+    /// the front of the stream.
+    pre_skip_remaining: u16,
+    /// The number of samples (at `decode_sample_rate`, post `pre_skip`) returned by
+    /// [`read_samples`] so far, used to trim encoder padding off the final page.
+    ///
+    /// [`read_samples`]: OggOpusReader::read_samples
+    samples_returned: u64,
     id_header: IdHeader,
     reader: PacketReader<R>,
 }
@@ -262,10 +538,55 @@ impl<R> OggOpusReader<R>
 where
     R: Read + Seek,
 {
-    /// Creates a new `OggOpusReader` from the given reader.
+    /// The decode sample rates Opus supports natively (RFC 6716 §2): 8, 12, 16, 24, and 48 kHz.
+    const VALID_SAMPLE_RATES: [u32; 5] = [8_000, 12_000, 16_000, 24_000, 48_000];
+
+    /// Creates a new `OggOpusReader` from the given reader, decoding at 48 kHz.
     pub fn new(reader: R) -> Result<Self> {
-        // temporary sample rate to decode at until better infrastructure is installed
-        const SAMPLE_RATE_TEMPORARY: u32 = 48_000;
+        Self::new_impl(reader, 48_000, false)
+    }
+
+    /// Creates a new `OggOpusReader` from the given reader, decoding at `sample_rate` Hz
+    /// instead of the usual 48 kHz.
+    ///
+    /// Opus can decode natively to 8000, 12000, 16000, 24000, or 48000 Hz; a lower rate trades
+    /// fidelity for less CPU and memory use, which is useful for low-power playback or to feed
+    /// a fixed-rate output device without resampling afterward. `pre_skip`, granule positions,
+    /// and this reader's own seeking math are all expressed at 48 kHz per the spec regardless
+    /// of `sample_rate`; [`read_samples`] and [`seek_to_sample`] rescale their output-sample
+    /// accounting to match.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`OggOpusError::UnsupportedSampleRate`] if `sample_rate` isn't one of the five
+    /// rates above.
+    ///
+    /// [`read_samples`]: OggOpusReader::read_samples
+    /// [`seek_to_sample`]: OggOpusReader::seek_to_sample
+    pub fn with_sample_rate(reader: R, sample_rate: u32) -> Result<Self> {
+        Self::new_impl(reader, sample_rate, false)
+    }
+
+    /// Creates a new `OggOpusReader` from the given reader, decoding at 48 kHz, retaining the
+    /// full Comment Header instead of truncating it at 61,440 bytes as usual.
+    ///
+    /// Embedded data past that point—most commonly a `METADATA_BLOCK_PICTURE` cover image—is
+    /// otherwise silently dropped; opt into this when such data is needed, e.g. before calling
+    /// [`pictures`](OggOpusReader::pictures).
+    pub fn with_full_comments(reader: R) -> Result<Self> {
+        Self::new_impl(reader, 48_000, true)
+    }
+
+    /// Combines [`with_sample_rate`](OggOpusReader::with_sample_rate) and
+    /// [`with_full_comments`](OggOpusReader::with_full_comments).
+    pub fn with_sample_rate_and_full_comments(reader: R, sample_rate: u32) -> Result<Self> {
+        Self::new_impl(reader, sample_rate, true)
+    }
+
+    fn new_impl(reader: R, sample_rate: u32, retain_full_comments: bool) -> Result<Self> {
+        if !Self::VALID_SAMPLE_RATES.contains(&sample_rate) {
+            return Err(OggOpusError::UnsupportedSampleRate.into());
+        }
 
         let mut reader = PacketReader::new(reader);
 
@@ -280,31 +601,101 @@ where
             };
 
         // read comment header
+        //
+        // Unlike the Identification Header above, the Comment Header has no paging constraint
+        // of its own—RFC 7845 explicitly allows it to span multiple pages (it's the usual home
+        // of an embedded `METADATA_BLOCK_PICTURE`, which can be large enough to need that), so
+        // there's nothing to check here beyond the packet having arrived at all.
         let comments_packet = reader.read_packet_expected()?;
-        let comments = if id_packet.first_in_page() && id_packet.last_in_page() {
-            CommentHeader::new(&comments_packet.data[..])?
+        let comments = if retain_full_comments {
+            CommentHeader::new_retaining_full(&comments_packet.data[..])?
         } else {
-            return Err(OggOpusError::BadPaging.into());
+            CommentHeader::new(&comments_packet.data[..])?
         };
 
         // initialize decoder
         let channels = id_header.channels().mapping_table().streams();
-        let decoder = Decoder::new(SAMPLE_RATE_TEMPORARY, channels);
+        let decoder = Decoder::new(sample_rate, channels);
+        let pre_skip_remaining =
+            (u64::from(id_header.pre_skip()) * u64::from(sample_rate) / 48_000) as u16;
 
         Ok(OggOpusReader {
             comments,
             decoder,
+            decode_sample_rate: sample_rate,
+            channels,
+            pre_skip_remaining,
+            samples_returned: 0,
             id_header,
             reader,
         })
     }
 
+    /// Returns the sample rate this reader's decoder produces output at, in Hz.
+    #[inline]
+    pub fn decode_sample_rate(&self) -> u32 {
+        self.decode_sample_rate
+    }
+
     /// Returns an iterator over user comments contained in the Vorbis comments block.
     #[inline]
     pub fn comments(&self) -> Comments<'_> {
         self.comments.comments()
     }
 
+    /// Case-insensitively looks up the first comment stored under `key` (e.g. `"TITLE"`,
+    /// `"ARTIST"`, `"ALBUM"`), per the Vorbis comment convention that field names compare
+    /// ASCII-case-insensitively.
+    #[inline]
+    pub fn comment(&self, key: &str) -> Option<&str> {
+        self.comments.get(key)
+    }
+
+    /// Case-insensitively looks up every comment stored under `key`. Most fields are
+    /// single-valued by convention, but some (e.g. `ARTIST`) are commonly repeated.
+    #[inline]
+    pub fn comments_named<'a>(&'a self, key: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.comments.get_all(key)
+    }
+
+    /// Sets `key` to `value`, replacing any existing comment(s) stored under it (compared
+    /// case-insensitively).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`OggOpusError::AllocationFailed`] if allocating storage for the rebuilt
+    /// comment data failed.
+    #[inline]
+    pub fn set_comment(&mut self, key: &str, value: &str) -> Result<()> {
+        self.comments.set(key, value)
+    }
+
+    /// Removes every comment stored under `key` (compared case-insensitively), returning how
+    /// many were removed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`OggOpusError::AllocationFailed`] if allocating storage for the rebuilt
+    /// comment data failed.
+    #[inline]
+    pub fn remove_comment(&mut self, key: &str) -> Result<usize> {
+        self.comments.remove(key)
+    }
+
+    /// Decodes every `METADATA_BLOCK_PICTURE` comment into a [`Picture`], silently skipping any
+    /// that aren't a validly base64-encoded FLAC picture block.
+    ///
+    /// Cover art is often larger than the 61,440-byte Comment Header truncation point this
+    /// reader applies by default—construct with [`with_full_comments`] or
+    /// [`with_sample_rate_and_full_comments`] first if pictures are unexpectedly missing.
+    ///
+    /// [`with_full_comments`]: OggOpusReader::with_full_comments
+    /// [`with_sample_rate_and_full_comments`]: OggOpusReader::with_sample_rate_and_full_comments
+    #[inline]
+    pub fn pictures(&self) -> impl Iterator<Item = Picture> + '_ {
+        self.comments.pictures()
+    }
+
     /// Returns the number of samples (at 48 kHz) to discard when beginning playback.
     #[inline]
     pub fn pre_skip(&self) -> u16 {
@@ -338,9 +729,30 @@ where
         self.id_header.version()
     }
 
+    /// Returns how many more samples (at `decode_sample_rate`) remain before the stream's real
+    /// end, given the final page's granule position (`last_page_granule`, at 48 kHz) and how
+    /// many samples (at `decode_sample_rate`) have already been returned to the caller.
+    ///
+    /// The granule position counts every decodable sample from the very start of the stream,
+    /// including `pre_skip`, so it's first rescaled down to the true PCM timeline before being
+    /// converted to the decoder's own output rate.
+    fn samples_remaining(
+        last_page_granule: u64,
+        pre_skip: u16,
+        decode_sample_rate: u32,
+        samples_returned: u64,
+    ) -> u64 {
+        let total_samples_48k = last_page_granule.saturating_sub(u64::from(pre_skip));
+        let total_samples = total_samples_48k * u64::from(decode_sample_rate) / 48_000;
+        total_samples.saturating_sub(samples_returned)
+    }
+
     /// A lower-level interface, decoding the next multipacket on each call.
     ///
-    /// Returns either an error, or the number of samples read per channel into `buf`.
+    /// Returns either an error, or the number of samples read per channel into `buf`. The
+    /// `pre_skip` samples at the start of the stream are discarded automatically, and the
+    /// final page's granule position is used to trim any encoder padding off the very last
+    /// samples, so every sample returned here is one a conformant player would render.
     pub fn read_samples<S, T>(&mut self, buf: &mut S) -> Result<usize>
     where
         S: Samples<T>,
@@ -348,15 +760,41 @@ where
     {
         use crate::multipacket::Multipacket;
 
-        let ogg_packet = self.reader.read_packet()?;
+        let ogg_packet = match self.reader.read_packet()? {
+            Some(ogg_packet) => ogg_packet,
+            None => return Ok(0),
+        };
+
+        let channels = usize::from(self.id_header.channels().mapping_table().channels());
+        let mapping_table = self.id_header.channels().mapping_table();
+        let multipacket = Multipacket::new(&ogg_packet.data[..], mapping_table)?;
+
+        let mut decoded: Vec<T> = Vec::new();
+        let total_decoded = self.decoder.decode(Some(multipacket), &mut decoded)?;
 
-        if let Some(ogg_packet) = ogg_packet {
-            let mapping_table = self.id_header.channels().mapping_table();
-            let multipacket = Multipacket::new(&ogg_packet.data[..], mapping_table)?;
-            Ok(self.decoder.decode(Some(multipacket), buf)?)
+        let skip = usize::from(self.pre_skip_remaining).min(total_decoded);
+        self.pre_skip_remaining -= skip as u16;
+        let available = total_decoded - skip;
+
+        let kept = if ogg_packet.last_in_stream() {
+            let remaining = Self::samples_remaining(
+                ogg_packet.absgp_page(),
+                self.id_header.pre_skip(),
+                self.decode_sample_rate,
+                self.samples_returned,
+            ) as usize;
+            available.min(remaining)
         } else {
-            Ok(0)
+            available
+        };
+
+        for i in 0..kept {
+            let frame = &decoded[(skip + i) * channels..(skip + i + 1) * channels];
+            buf.write_frame(i, frame);
         }
+
+        self.samples_returned += kept as u64;
+        Ok(kept)
     }
 
     /// Returns the wrapped reader, consuming the `OggOpusReader`.
@@ -364,6 +802,169 @@ where
     pub fn into_inner(self) -> R {
         self.reader.into_inner()
     }
+
+    /// Seeks so that the next call to [`read_samples`] resumes at `sample_48k`, a sample index
+    /// (at 48&nbsp;kHz, per the granule position convention) into the file's decoded PCM
+    /// timeline—i.e. counted from the end of the ID header's [`pre_skip`], so sample `0` is
+    /// granule position `pre_skip`.
+    ///
+    /// Every Ogg Opus page's granule position is the total number of decodable output samples
+    /// (at 48 kHz) through the end of that page, so the true PCM timeline position of a sample
+    /// is `granule - pre_skip`. This binary-searches byte offsets in the underlying reader,
+    /// narrowing to the page containing the target by comparing each candidate page's granule
+    /// position, then resets the decoder and replays about 80&nbsp;ms (3840 samples at 48 kHz)
+    /// of pre-roll from the start of that page, discarding the decoded audio, so the SILK/CELT
+    /// filter and pitch-prediction state has converged again by the time decoding reaches
+    /// `sample_48k`.
+    ///
+    /// Since `sample_48k` already excludes `pre_skip`, `0` is the very beginning of the decoded
+    /// audio and no target lies before it. Seeking past
+    /// the final page's granule position seeks to end-of-stream, after which `read_samples`
+    /// returns `Ok(0)`.
+    ///
+    /// [`read_samples`]: OggOpusReader::read_samples
+    /// [`pre_skip`]: OggOpusReader::pre_skip
+    pub fn seek_to_sample(&mut self, sample_48k: u64) -> Result<()> {
+        use std::io::SeekFrom;
+
+        /// The amount of audio to decode and discard after a seek before trusting the decoder's
+        /// state, per the common ~80 ms Opus pre-roll recommendation.
+        const PRE_ROLL_SAMPLES: u64 = 3_840;
+
+        let target_48k = sample_48k.saturating_add(u64::from(self.pre_skip()));
+
+        let end = self.reader.seek_bytes(SeekFrom::End(0))?;
+        let pre_roll_target = target_48k.saturating_sub(PRE_ROLL_SAMPLES);
+
+        let mut lo = 0u64;
+        let mut hi = end;
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            self.reader.seek_bytes(SeekFrom::Start(mid))?;
+
+            match self.reader.read_packet()? {
+                Some(packet) if packet.absgp_page() >= pre_roll_target => hi = mid,
+                Some(_) => lo = mid + 1,
+                None => hi = mid,
+            }
+        }
+
+        self.reader.seek_bytes(SeekFrom::Start(lo))?;
+        self.decoder = Decoder::new(self.decode_sample_rate, self.channels);
+
+        // `target_48k` already accounts for `pre_skip` as a granule-space offset, rather than an
+        // additional amount to discard, so the usual start-of-stream skip must not also apply.
+        self.pre_skip_remaining = 0;
+
+        // `read_samples`'s own end-of-stream trim (`total_samples - samples_returned`) runs on
+        // every call inside the discard loop below too, not just once the loop is done, so
+        // `samples_returned` must already approximate the decode position at the start of page
+        // `lo`'s audio before the loop starts—left at its pre-seek value, a seek landing on or
+        // near the final page would mis-trim during the discard itself. `pre_roll_target` is
+        // exactly that position, in the granule domain, sans the `pre_skip` offset.
+        let pre_roll_position = pre_roll_target.saturating_sub(u64::from(self.pre_skip()));
+        self.samples_returned = pre_roll_position * u64::from(self.decode_sample_rate) / 48_000;
+
+        // Decode and discard the pre-roll, and anything still short of the real target, in
+        // this reader's own decode-rate domain rather than the 48 kHz granule domain.
+        //
+        // `self.samples_returned` is already on the same absolute (from true position zero)
+        // scale as `target`—it was just seeded from `pre_roll_position` above, and
+        // `read_samples` advances it by every sample it returns, discarded or not—so comparing
+        // it directly against `target` is what actually stops the loop at the right place; a
+        // separate counter started from zero here would be counting from page `lo`'s position
+        // instead of from the start of the stream, and so would almost never agree with
+        // `target` until long past it (or past EOF).
+        let target = sample_48k * u64::from(self.decode_sample_rate) / 48_000;
+        let mut scratch: Vec<f32> = Vec::new();
+        while self.samples_returned < target {
+            scratch.clear();
+            if self.read_samples(&mut scratch)? == 0 {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// A convenience wrapper around [`seek_to_sample`] taking a playback position as a
+    /// [`Duration`] rather than a raw 48 kHz sample index.
+    ///
+    /// [`seek_to_sample`]: OggOpusReader::seek_to_sample
+    pub fn seek_to_duration(&mut self, duration: std::time::Duration) -> Result<()> {
+        let sample_48k = (duration.as_micros() as u64 * 48_000) / 1_000_000;
+        self.seek_to_sample(sample_48k)
+    }
+
+    /// Returns the total length of the stream, in samples at 48 kHz (not counting `pre_skip`),
+    /// without decoding a single frame.
+    ///
+    /// This seeks to the end of the underlying reader to read the final page's granule
+    /// position, then restores the prior read position, so it only works for a seekable `R`—
+    /// [`OggOpusStream`] has no equivalent, since it never gets to see the end of a live stream
+    /// until the stream itself ends.
+    ///
+    /// Returns `Ok(None)` if the final page doesn't carry an end-of-stream marker, or its
+    /// granule position is undefined (`-1`, per [RFC 3533 § 6]); either way, the stream's true
+    /// length isn't knowable without a full decode.
+    ///
+    /// [RFC 3533 § 6]: https://tools.ietf.org/html/rfc3533#section-6
+    pub fn total_samples(&mut self) -> Result<Option<u64>> {
+        use std::io::SeekFrom;
+
+        // Comfortably larger than the largest possible Ogg page (27-byte header + 255-byte
+        // segment table + 255 segments of up to 255 bytes each), so the first attempt almost
+        // always already contains the whole final page; doubled and retried otherwise.
+        const INITIAL_WINDOW: u64 = 65_536;
+
+        let prior_position = self.reader.seek_bytes(SeekFrom::Current(0))?;
+
+        // Run the scan in a closure so any I/O or resync error partway through—e.g. the
+        // non-page-aligned `end - window` starting offset failing to resync to a page
+        // boundary—still falls through to restoring `prior_position` below, rather than
+        // leaving the underlying reader at whatever offset the scan last seeked to.
+        let scan = |reader: &mut PacketReader<R>| -> Result<Option<u64>> {
+            let end = reader.seek_bytes(SeekFrom::End(0))?;
+            let mut window = INITIAL_WINDOW.min(end);
+
+            loop {
+                reader.seek_bytes(SeekFrom::Start(end - window))?;
+
+                let mut last_page_granule = None;
+                while let Some(packet) = reader.read_packet()? {
+                    if packet.last_in_stream() {
+                        last_page_granule = Some(packet.absgp_page());
+                    }
+                }
+
+                match last_page_granule {
+                    Some(granule) => break Ok(Some(granule)),
+                    None if window >= end => break Ok(None),
+                    None => window = (window * 2).min(end),
+                }
+            }
+        };
+
+        let result = scan(&mut self.reader);
+        self.reader.seek_bytes(SeekFrom::Start(prior_position))?;
+
+        // An all-ones granule position (`-1` in two's complement) is explicitly undefined by
+        // the Ogg spec, used when a page shouldn't be treated as an end-of-stream marker for
+        // this purpose even though its `last_in_stream` bit is set.
+        Ok(result?
+            .filter(|&granule| granule != u64::MAX)
+            .map(|granule| granule.saturating_sub(u64::from(self.id_header.pre_skip()))))
+    }
+
+    /// A convenience wrapper around [`total_samples`] reporting the stream's length as a
+    /// [`Duration`](std::time::Duration) instead of a raw 48 kHz sample count.
+    ///
+    /// [`total_samples`]: OggOpusReader::total_samples
+    pub fn duration(&mut self) -> Result<Option<std::time::Duration>> {
+        Ok(self
+            .total_samples()?
+            .map(|samples| std::time::Duration::from_micros(samples * 1_000_000 / 48_000)))
+    }
 }
 
 impl<R> Debug for OggOpusReader<R>
@@ -386,3 +987,653 @@ where
             .finish()
     }
 }
+
+/// What has been established so far about an [`OggOpusStream`]'s logical stream: the first two
+/// packets are always the ID and Comment headers, and every packet after that is audio.
+enum StreamState {
+    AwaitingId,
+    AwaitingComments(IdHeader),
+    Streaming {
+        id_header: IdHeader,
+        comments: CommentHeader,
+        decoder: Decoder,
+    },
+}
+
+/// A push-driven, `Seek`-free Ogg Opus demuxer for live streams, sockets, or other contexts
+/// that only ever hand over bytes as they arrive.
+///
+/// Where [`OggOpusReader`] pulls from a blocking `Read + Seek` via [`ogg::PacketReader`],
+/// `OggOpusStream` is fed with [`push`], which buffers the incoming bytes and reassembles
+/// complete Ogg pages—and the packets they carry, including packets whose segments continue
+/// onto a later page—entirely on its own, with no lookahead or backward seeking. Once the ID
+/// and Comment headers have arrived, every later packet is decoded audio, retrieved one at a
+/// time with [`next_frame`].
+///
+/// [`push`]: OggOpusStream::push
+/// [`next_frame`]: OggOpusStream::next_frame
+pub struct OggOpusStream {
+    /// Bytes received via [`push`](OggOpusStream::push) but not yet reassembled into a
+    /// complete page.
+    buf: Vec<u8>,
+    /// A packet whose segments continue onto a later page, accumulated so far.
+    pending_packet: Vec<u8>,
+    /// Complete packets reassembled from pages, oldest first, waiting to be consumed by
+    /// [`next_frame`](OggOpusStream::next_frame).
+    packets: VecDeque<Vec<u8>>,
+    /// Whether to retain the full Comment Header rather than truncate it; see
+    /// [`with_full_comments`](OggOpusStream::with_full_comments).
+    retain_full_comments: bool,
+    /// The number of samples (at 48 kHz) of `pre_skip` still to be discarded from the front of
+    /// the decoded stream; set from the ID Header's `pre_skip` once it arrives.
+    pre_skip_remaining: u16,
+    state: StreamState,
+}
+
+impl OggOpusStream {
+    /// The Ogg page capture pattern.
+    const CAPTURE_PATTERN: [u8; 4] = *b"OggS";
+
+    /// The fixed length of an Ogg page header, up to but not including its segment table.
+    const PAGE_HEADER_LEN: usize = 27;
+
+    /// The sample rate the decoder is built to produce output at.
+    const SAMPLE_RATE: u32 = 48_000;
+
+    /// Creates an empty stream, ready to be fed with [`push`](OggOpusStream::push).
+    pub fn new() -> Self {
+        OggOpusStream {
+            buf: Vec::new(),
+            pending_packet: Vec::new(),
+            packets: VecDeque::new(),
+            retain_full_comments: false,
+            pre_skip_remaining: 0,
+            state: StreamState::AwaitingId,
+        }
+    }
+
+    /// Creates an empty stream like [`new`](OggOpusStream::new), but retaining the full Comment
+    /// Header instead of truncating it at 61,440 bytes as usual—see
+    /// [`OggOpusReader::with_full_comments`] for why this matters for cover art.
+    pub fn with_full_comments() -> Self {
+        OggOpusStream {
+            retain_full_comments: true,
+            ..OggOpusStream::new()
+        }
+    }
+
+    /// Buffers `data`, reassembling every complete Ogg page—and the packets within it—that can
+    /// be formed from the bytes received so far.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`OggOpusError::BadMagic`] if the buffered bytes don't begin with a page's
+    /// capture pattern once a full page header has arrived; unlike a real-world demuxer facing
+    /// a corrupt stream, this does not attempt to resynchronize to a later page.
+    pub fn push(&mut self, data: &[u8]) -> Result<()> {
+        self.buf.extend_from_slice(data);
+
+        while let Some(page_len) = self.take_page()? {
+            self.buf.drain(..page_len);
+        }
+
+        Ok(())
+    }
+
+    /// If a complete page is buffered, reassembles its packets into `self.packets` (and
+    /// `self.pending_packet`, if the page's last packet continues onto the next one) and
+    /// returns the page's total length in bytes; returns `None` if more data is needed.
+    fn take_page(&mut self) -> Result<Option<usize>> {
+        if self.buf.len() < Self::PAGE_HEADER_LEN {
+            return Ok(None);
+        }
+
+        if self.buf[..4] != Self::CAPTURE_PATTERN {
+            return Err(OggOpusError::BadMagic.into());
+        }
+
+        let header_type = self.buf[5];
+        let page_segments = usize::from(self.buf[26]);
+        let table_end = Self::PAGE_HEADER_LEN + page_segments;
+
+        if self.buf.len() < table_end {
+            return Ok(None);
+        }
+
+        let segment_table = self.buf[Self::PAGE_HEADER_LEN..table_end].to_vec();
+        let body_len: usize = segment_table.iter().map(|&lacing| usize::from(lacing)).sum();
+        let page_len = table_end + body_len;
+
+        if self.buf.len() < page_len {
+            return Ok(None);
+        }
+
+        // The "continued packet" flag must agree with whether a packet is actually pending;
+        // a mismatch means the pages aren't aligned the way this stream expects.
+        let continued = header_type & 0x01 != 0;
+        if continued != !self.pending_packet.is_empty() {
+            return Err(OggOpusError::BadPaging.into());
+        }
+
+        let body = &self.buf[table_end..page_len];
+        let mut run_start = 0;
+        let mut offset = 0;
+
+        for &lacing in &segment_table {
+            offset += usize::from(lacing);
+
+            // A lacing value under 255 terminates a packet; a run of 255s continues it into
+            // the next segment (and, at the end of the table, onto the next page).
+            if lacing < 255 {
+                let segment = &body[run_start..offset];
+                run_start = offset;
+
+                if self.pending_packet.is_empty() {
+                    self.packets.push_back(segment.to_vec());
+                } else {
+                    self.pending_packet.extend_from_slice(segment);
+                    self.packets
+                        .push_back(std::mem::take(&mut self.pending_packet));
+                }
+            }
+        }
+
+        if run_start < body.len() {
+            self.pending_packet.extend_from_slice(&body[run_start..]);
+        }
+
+        Ok(Some(page_len))
+    }
+
+    /// Decodes the next fully-buffered packet.
+    ///
+    /// The first two packets of a logical stream are always the ID and Comment headers, which
+    /// are parsed and stored internally—see [`comments`](OggOpusStream::comments) and
+    /// [`pre_skip`](OggOpusStream::pre_skip), both of which return `None` until the headers have
+    /// arrived. Every packet after that is decoded audio, written into `buf`; the `pre_skip`
+    /// samples at the start of the stream are discarded automatically, same as
+    /// [`OggOpusReader::read_samples`].
+    ///
+    /// Returns `Ok(None)` if no complete packet has been reassembled yet; call
+    /// [`push`](OggOpusStream::push) with more data and try again.
+    pub fn next_frame<S, T>(&mut self, buf: &mut S) -> Result<Option<usize>>
+    where
+        S: Samples<T>,
+        T: Sample,
+    {
+        use crate::multipacket::Multipacket;
+
+        loop {
+            let packet = match self.packets.pop_front() {
+                Some(packet) => packet,
+                None => return Ok(None),
+            };
+
+            self.state = match std::mem::replace(&mut self.state, StreamState::AwaitingId) {
+                StreamState::AwaitingId => StreamState::AwaitingComments(IdHeader::new(&packet)?),
+                StreamState::AwaitingComments(id_header) => {
+                    let comments = if self.retain_full_comments {
+                        CommentHeader::new_retaining_full(&packet)?
+                    } else {
+                        CommentHeader::new(&packet)?
+                    };
+                    let channels = id_header.channels().mapping_table().streams();
+                    let decoder = Decoder::new(Self::SAMPLE_RATE, channels);
+                    self.pre_skip_remaining = id_header.pre_skip();
+
+                    StreamState::Streaming {
+                        id_header,
+                        comments,
+                        decoder,
+                    }
+                }
+                StreamState::Streaming {
+                    id_header,
+                    comments,
+                    mut decoder,
+                } => {
+                    let out_channels = usize::from(id_header.channels().mapping_table().channels());
+                    let mapping_table = id_header.channels().mapping_table();
+                    let multipacket = Multipacket::new(&packet[..], mapping_table)?;
+
+                    let mut decoded: Vec<T> = Vec::new();
+                    let total_decoded = decoder.decode(Some(multipacket), &mut decoded)?;
+
+                    let skip = usize::from(self.pre_skip_remaining).min(total_decoded);
+                    self.pre_skip_remaining -= skip as u16;
+
+                    for i in skip..total_decoded {
+                        let frame = &decoded[i * out_channels..(i + 1) * out_channels];
+                        buf.write_frame(i - skip, frame);
+                    }
+
+                    self.state = StreamState::Streaming {
+                        id_header,
+                        comments,
+                        decoder,
+                    };
+                    return Ok(Some(total_decoded - skip));
+                }
+            };
+        }
+    }
+
+    /// Returns an iterator over user comments, once the Comment Header has been decoded.
+    pub fn comments(&self) -> Option<Comments<'_>> {
+        match &self.state {
+            StreamState::Streaming { comments, .. } => Some(comments.comments()),
+            _ => None,
+        }
+    }
+
+    /// Case-insensitively looks up the first comment stored under `key`, once the Comment
+    /// Header has been decoded.
+    pub fn comment(&self, key: &str) -> Option<&str> {
+        match &self.state {
+            StreamState::Streaming { comments, .. } => comments.get(key),
+            _ => None,
+        }
+    }
+
+    /// Decodes every `METADATA_BLOCK_PICTURE` comment into a [`Picture`], once the Comment
+    /// Header has been decoded, silently skipping any that aren't a validly base64-encoded
+    /// FLAC picture block.
+    ///
+    /// Cover art is often larger than the 61,440-byte Comment Header truncation point this
+    /// stream applies by default—construct with [`with_full_comments`] first if pictures are
+    /// unexpectedly missing.
+    ///
+    /// [`with_full_comments`]: OggOpusStream::with_full_comments
+    pub fn pictures(&self) -> Option<impl Iterator<Item = Picture> + '_> {
+        match &self.state {
+            StreamState::Streaming { comments, .. } => Some(comments.pictures()),
+            _ => None,
+        }
+    }
+
+    /// Returns the number of samples (at 48 kHz) to discard when beginning playback, once the
+    /// ID Header has been decoded.
+    pub fn pre_skip(&self) -> Option<u16> {
+        match &self.state {
+            StreamState::AwaitingId => None,
+            StreamState::AwaitingComments(id_header) => Some(id_header.pre_skip()),
+            StreamState::Streaming { id_header, .. } => Some(id_header.pre_skip()),
+        }
+    }
+}
+
+impl Default for OggOpusStream {
+    fn default() -> Self {
+        OggOpusStream::new()
+    }
+}
+
+impl Debug for OggOpusStream {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OggOpusStream")
+            .field("buf_len", &self.buf.len())
+            .field("pending_packet_len", &self.pending_packet.len())
+            .field("packets_queued", &self.packets.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod comment_tests {
+    use super::*;
+
+    /// Builds a raw Comment Header packet (`OpusTags` plus vendor string and comment list).
+    fn comment_header_bytes(vendor: &str, comments: &[(&str, &str)]) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend_from_slice(&CommentHeader::MAGIC);
+        data.extend_from_slice(&(vendor.len() as u32).to_le_bytes());
+        data.extend_from_slice(vendor.as_bytes());
+        data.extend_from_slice(&(comments.len() as u32).to_le_bytes());
+
+        for (key, value) in comments {
+            let entry = format!("{}={}", key, value);
+            data.extend_from_slice(&(entry.len() as u32).to_le_bytes());
+            data.extend_from_slice(entry.as_bytes());
+        }
+
+        data
+    }
+
+    #[test]
+    fn round_trips_vendor_and_looks_up_comments_case_insensitively() {
+        let data = comment_header_bytes("test vendor", &[("TITLE", "Song"), ("ARTIST", "Band")]);
+        let header = CommentHeader::new(&data).unwrap();
+
+        assert_eq!(header.vendor(), "test vendor");
+        assert_eq!(header.get("title"), Some("Song"));
+        assert_eq!(header.get("ARTIST"), Some("Band"));
+        assert_eq!(header.get("missing"), None);
+    }
+
+    #[test]
+    fn comments_iterator_resumes_past_a_malformed_entry() {
+        let mut data = Vec::new();
+        data.extend_from_slice(&CommentHeader::MAGIC);
+        data.extend_from_slice(&0u32.to_le_bytes()); // empty vendor
+        data.extend_from_slice(&3u32.to_le_bytes()); // comments_num
+
+        let good1 = b"TITLE=Good One";
+        data.extend_from_slice(&(good1.len() as u32).to_le_bytes());
+        data.extend_from_slice(good1);
+
+        let bad = b"NO SEPARATOR HERE"; // malformed: no '=' to split on
+        data.extend_from_slice(&(bad.len() as u32).to_le_bytes());
+        data.extend_from_slice(bad);
+
+        let good2 = b"ARTIST=Someone";
+        data.extend_from_slice(&(good2.len() as u32).to_le_bytes());
+        data.extend_from_slice(good2);
+
+        let header = CommentHeader::new(&data).unwrap();
+        let collected: Vec<_> = header.comments().collect();
+
+        assert_eq!(collected, vec![("TITLE", "Good One"), ("ARTIST", "Someone")]);
+    }
+
+    #[test]
+    fn set_replaces_the_matching_key_and_preserves_the_rest() {
+        let data = comment_header_bytes("v", &[("Title", "Old"), ("Artist", "Band")]);
+        let mut header = CommentHeader::new(&data).unwrap();
+
+        header.set("TITLE", "New").unwrap();
+
+        let collected: Vec<(String, String)> = header
+            .comments()
+            .map(|(k, v)| (k.to_owned(), v.to_owned()))
+            .collect();
+
+        assert_eq!(collected.len(), 2);
+        assert!(collected.contains(&("TITLE".to_owned(), "New".to_owned())));
+        assert!(collected.contains(&("Artist".to_owned(), "Band".to_owned())));
+    }
+
+    #[test]
+    fn set_does_not_drop_comments_that_follow_a_malformed_one() {
+        // Regression test: `rebuild` used to silently drop every comment after a malformed one,
+        // because `Comments::next` stopped iterating the first time it hit one.
+        let mut data = Vec::new();
+        data.extend_from_slice(&CommentHeader::MAGIC);
+        data.extend_from_slice(&0u32.to_le_bytes());
+        data.extend_from_slice(&2u32.to_le_bytes());
+
+        let bad = b"NO SEPARATOR HERE";
+        data.extend_from_slice(&(bad.len() as u32).to_le_bytes());
+        data.extend_from_slice(bad);
+
+        let good = b"ARTIST=Someone";
+        data.extend_from_slice(&(good.len() as u32).to_le_bytes());
+        data.extend_from_slice(good);
+
+        let mut header = CommentHeader::new(&data).unwrap();
+        header.set("TITLE", "New").unwrap();
+
+        let collected: Vec<(String, String)> = header
+            .comments()
+            .map(|(k, v)| (k.to_owned(), v.to_owned()))
+            .collect();
+
+        assert!(collected.contains(&("ARTIST".to_owned(), "Someone".to_owned())));
+        assert!(collected.contains(&("TITLE".to_owned(), "New".to_owned())));
+    }
+
+    #[test]
+    fn remove_reports_how_many_comments_were_removed() {
+        let data = comment_header_bytes(
+            "v",
+            &[("Genre", "Rock"), ("Genre", "Pop"), ("Artist", "Band")],
+        );
+        let mut header = CommentHeader::new(&data).unwrap();
+
+        let removed = header.remove("genre").unwrap();
+
+        assert_eq!(removed, 2);
+        assert_eq!(header.get("genre"), None);
+        assert_eq!(header.get("Artist"), Some("Band"));
+    }
+
+    #[test]
+    fn picture_decodes_a_base64_encoded_flac_picture_block() {
+        let mut block = Vec::new();
+        block.extend_from_slice(&3u32.to_be_bytes()); // picture_type: Cover (front)
+
+        let mime = b"image/png";
+        block.extend_from_slice(&(mime.len() as u32).to_be_bytes());
+        block.extend_from_slice(mime);
+
+        let description = b"cover";
+        block.extend_from_slice(&(description.len() as u32).to_be_bytes());
+        block.extend_from_slice(description);
+
+        block.extend_from_slice(&100u32.to_be_bytes()); // width
+        block.extend_from_slice(&200u32.to_be_bytes()); // height
+        block.extend_from_slice(&24u32.to_be_bytes()); // depth
+        block.extend_from_slice(&0u32.to_be_bytes()); // colors
+
+        let image_data = b"\x89PNGfakebytes";
+        block.extend_from_slice(&(image_data.len() as u32).to_be_bytes());
+        block.extend_from_slice(image_data);
+
+        let encoded = base64::encode(&block);
+        let picture = Picture::decode(&encoded).unwrap();
+
+        assert_eq!(picture.picture_type(), 3);
+        assert_eq!(picture.mime_type(), "image/png");
+        assert_eq!(picture.description(), "cover");
+        assert_eq!(picture.width(), 100);
+        assert_eq!(picture.height(), 200);
+        assert_eq!(picture.data(), &image_data[..]);
+    }
+}
+
+#[cfg(test)]
+mod stream_tests {
+    use super::*;
+
+    /// Builds a raw Ogg page. Every header field but `header_type` and the segment table is
+    /// zeroed, since `OggOpusStream::take_page` reads neither the granule position, serial
+    /// number, page sequence, nor checksum.
+    fn make_page(header_type: u8, segment_table: &[u8], body: &[u8]) -> Vec<u8> {
+        let mut page = Vec::new();
+        page.extend_from_slice(&OggOpusStream::CAPTURE_PATTERN);
+        page.push(0); // version
+        page.push(header_type);
+        page.extend_from_slice(&[0u8; 20]); // granule position, serial, page sequence, checksum
+        page.push(segment_table.len() as u8);
+        page.extend_from_slice(segment_table);
+        page.extend_from_slice(body);
+        page
+    }
+
+    #[test]
+    fn push_reassembles_every_complete_packet_in_one_page() {
+        let packet_a = b"hello";
+        let packet_b = b"world!";
+        let segment_table = [packet_a.len() as u8, packet_b.len() as u8];
+        let mut body = Vec::new();
+        body.extend_from_slice(packet_a);
+        body.extend_from_slice(packet_b);
+
+        let mut stream = OggOpusStream::new();
+        stream.push(&make_page(0, &segment_table, &body)).unwrap();
+
+        assert_eq!(stream.packets.len(), 2);
+        assert_eq!(&stream.packets[0][..], packet_a);
+        assert_eq!(&stream.packets[1][..], packet_b);
+    }
+
+    #[test]
+    fn push_reassembles_a_packet_continued_onto_a_later_page() {
+        // A lacing value of 255 continues a packet into the next segment, or—at the end of the
+        // table—onto the next page.
+        let part1 = vec![7u8; 255];
+        let page1 = make_page(0, &[255], &part1);
+
+        let part2 = vec![9u8; 10];
+        let page2 = make_page(1, &[part2.len() as u8], &part2); // header_type bit 0: continued
+
+        let mut stream = OggOpusStream::new();
+        stream.push(&page1).unwrap();
+        assert!(stream.packets.is_empty(), "packet isn't complete yet");
+
+        stream.push(&page2).unwrap();
+        assert_eq!(stream.packets.len(), 1);
+
+        let mut expected = part1;
+        expected.extend_from_slice(&part2);
+        assert_eq!(stream.packets[0], expected);
+    }
+
+    #[test]
+    fn push_reassembles_a_page_delivered_in_arbitrary_byte_chunks() {
+        // A push-based consumer (e.g. reading off a live socket) has no guarantee that what it
+        // hands to `push` lines up with page boundaries at all.
+        let packet = b"fragmented";
+        let page = make_page(0, &[packet.len() as u8], packet);
+
+        let mut stream = OggOpusStream::new();
+        for chunk in page.chunks(3) {
+            stream.push(chunk).unwrap();
+        }
+
+        assert_eq!(stream.packets.len(), 1);
+        assert_eq!(&stream.packets[0][..], &packet[..]);
+    }
+
+    #[test]
+    fn push_rejects_a_continued_flag_with_no_packet_actually_pending() {
+        let page = make_page(1, &[5], &[0u8; 5]);
+
+        let mut stream = OggOpusStream::new();
+        assert!(stream.push(&page).is_err());
+    }
+
+    #[test]
+    fn pre_skip_is_unknown_before_the_id_header_arrives() {
+        let stream = OggOpusStream::new();
+        assert_eq!(stream.pre_skip(), None);
+    }
+}
+
+#[cfg(test)]
+mod reader_tests {
+    use super::*;
+    use std::io::Cursor;
+
+    /// `OggOpusReader<R>::samples_remaining` doesn't touch `R`, so any concrete type works here.
+    type Reader = OggOpusReader<Cursor<Vec<u8>>>;
+
+    #[test]
+    fn samples_remaining_subtracts_pre_skip_and_rescales_to_the_decode_rate() {
+        // 48,000 granule samples, 1,000 of which are pre_skip: 47,000 true samples, halved by
+        // decoding at 24 kHz instead of 48 kHz.
+        let remaining = Reader::samples_remaining(48_000, 1_000, 24_000, 0);
+        assert_eq!(remaining, 23_500);
+    }
+
+    #[test]
+    fn samples_remaining_subtracts_what_has_already_been_returned() {
+        let remaining = Reader::samples_remaining(48_000, 0, 48_000, 40_000);
+        assert_eq!(remaining, 8_000);
+    }
+
+    #[test]
+    fn samples_remaining_saturates_instead_of_underflowing() {
+        // More has been "returned" than the granule position accounts for—e.g. a seek whose
+        // discard loop overshot the final page slightly—so there's nothing left to trim to.
+        let remaining = Reader::samples_remaining(48_000, 0, 48_000, 100_000);
+        assert_eq!(remaining, 0);
+
+        // A granule position smaller than `pre_skip` itself (a pathologically short stream)
+        // must not underflow either.
+        let remaining = Reader::samples_remaining(500, 1_000, 48_000, 0);
+        assert_eq!(remaining, 0);
+    }
+
+    /// Builds a real, page-bisectable Ogg Opus stream: an ID Header and Comment Header each on
+    /// their own page, followed by `page_count` pages of one mono, 20 ms silence packet apiece
+    /// (a lone TOC byte—CELT fullband, single frame—whose implicit frame length, packet length
+    /// minus one, is zero).
+    fn multi_page_stream(page_count: u64) -> Vec<u8> {
+        use ogg::{PacketWriteEndInfo, PacketWriter};
+
+        const SERIAL: u32 = 1;
+
+        let mut bytes = Vec::new();
+        let mut writer = PacketWriter::new(&mut bytes);
+
+        let mut id_header = Vec::new();
+        id_header.extend_from_slice(b"OpusHead");
+        id_header.push(1); // version
+        id_header.push(1); // channel count: mono
+        id_header.extend_from_slice(&0u16.to_le_bytes()); // pre_skip
+        id_header.extend_from_slice(&0u32.to_le_bytes()); // input sample rate (informational)
+        id_header.extend_from_slice(&0i16.to_le_bytes()); // output gain
+        id_header.push(0); // channel mapping family 0: no mapping table follows
+        writer
+            .write_packet(id_header, SERIAL, PacketWriteEndInfo::EndPage, 0)
+            .unwrap();
+
+        let mut comments = Vec::new();
+        comments.extend_from_slice(&CommentHeader::MAGIC);
+        comments.extend_from_slice(&0u32.to_le_bytes()); // empty vendor string
+        comments.extend_from_slice(&0u32.to_le_bytes()); // no comments
+        writer
+            .write_packet(comments, SERIAL, PacketWriteEndInfo::EndPage, 0)
+            .unwrap();
+
+        for page in 1..=page_count {
+            let end_info = if page == page_count {
+                PacketWriteEndInfo::EndStream
+            } else {
+                PacketWriteEndInfo::EndPage
+            };
+            writer
+                .write_packet(
+                    vec![0xF8], // TOC: CELT fullband, mono, single frame, 20 ms
+                    SERIAL,
+                    end_info,
+                    page * FRAME_SAMPLES,
+                )
+                .unwrap();
+        }
+
+        bytes
+    }
+
+    /// 20 ms at 48 kHz—the frame size [`multi_page_stream`]'s silence packets encode to.
+    const FRAME_SAMPLES: u64 = 960;
+
+    #[test]
+    fn seek_to_sample_lands_near_the_requested_sample_in_a_multi_page_stream() {
+        use std::io::Cursor;
+
+        const PAGE_COUNT: u64 = 40;
+
+        let bytes = multi_page_stream(PAGE_COUNT);
+        let mut reader = OggOpusReader::new(Cursor::new(bytes)).unwrap();
+
+        // Seek into the middle of the stream—well past the first pre-roll window, which is
+        // exactly the case the discard loop's absolute-vs-relative bug broke.
+        let target = PAGE_COUNT * FRAME_SAMPLES / 2;
+        reader.seek_to_sample(target).unwrap();
+
+        let mut buf: Vec<f32> = Vec::new();
+        let samples = reader.read_samples(&mut buf).unwrap();
+        assert!(samples > 0, "seek left the reader sitting at end-of-stream");
+
+        // The buggy loop counted from page `lo`'s position instead of from the start of the
+        // stream, so it ran to EOF well before reaching `target`—landing far short of it
+        // rather than within a frame.
+        let diff = reader.samples_returned.abs_diff(target);
+        assert!(
+            diff <= FRAME_SAMPLES,
+            "expected to land near sample {target}, landed at {}",
+            reader.samples_returned
+        );
+    }
+}
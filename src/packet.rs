@@ -220,6 +220,52 @@ impl Config {
     pub(crate) fn frame_size(self) -> FrameSize {
         self.frame_size
     }
+
+    /// Returns the config number (0–31) encoding this mode/bandwidth/frame-size
+    /// combination, per Table 2 of RFC 6716. The inverse of [`From<ConfigNumber> for
+    /// Config`](struct.Config.html#impl-From%3CConfigNumber%3E).
+    pub(crate) fn to_config_number(self) -> u8 {
+        use Bandwidth::*;
+        use Mode::*;
+
+        match (self.mode, self.bandwidth, self.frame_size) {
+            (Silk, Narrowband, FrameSize::Ten) => 0,
+            (Silk, Narrowband, FrameSize::Twenty) => 1,
+            (Silk, Narrowband, FrameSize::Fourty) => 2,
+            (Silk, Narrowband, FrameSize::Sixty) => 3,
+            (Silk, MediumBand, FrameSize::Ten) => 4,
+            (Silk, MediumBand, FrameSize::Twenty) => 5,
+            (Silk, MediumBand, FrameSize::Fourty) => 6,
+            (Silk, MediumBand, FrameSize::Sixty) => 7,
+            (Silk, Wideband, FrameSize::Ten) => 8,
+            (Silk, Wideband, FrameSize::Twenty) => 9,
+            (Silk, Wideband, FrameSize::Fourty) => 10,
+            (Silk, Wideband, FrameSize::Sixty) => 11,
+            (Hybrid, SuperWideband, FrameSize::Ten) => 12,
+            (Hybrid, SuperWideband, FrameSize::Twenty) => 13,
+            (Hybrid, Fullband, FrameSize::Ten) => 14,
+            (Hybrid, Fullband, FrameSize::Twenty) => 15,
+            (Celt, Narrowband, FrameSize::TwoPointFive) => 16,
+            (Celt, Narrowband, FrameSize::Five) => 17,
+            (Celt, Narrowband, FrameSize::Ten) => 18,
+            (Celt, Narrowband, FrameSize::Twenty) => 19,
+            (Celt, Wideband, FrameSize::TwoPointFive) => 20,
+            (Celt, Wideband, FrameSize::Five) => 21,
+            (Celt, Wideband, FrameSize::Ten) => 22,
+            (Celt, Wideband, FrameSize::Twenty) => 23,
+            (Celt, SuperWideband, FrameSize::TwoPointFive) => 24,
+            (Celt, SuperWideband, FrameSize::Five) => 25,
+            (Celt, SuperWideband, FrameSize::Ten) => 26,
+            (Celt, SuperWideband, FrameSize::Twenty) => 27,
+            (Celt, Fullband, FrameSize::TwoPointFive) => 28,
+            (Celt, Fullband, FrameSize::Five) => 29,
+            (Celt, Fullband, FrameSize::Ten) => 30,
+            (Celt, Fullband, FrameSize::Twenty) => 31,
+            // Every other tuple is unreachable: `Config` is only ever constructed from a valid
+            // `ConfigNumber`, whose `From` impls only ever produce these combinations.
+            _ => unreachable!(),
+        }
+    }
 }
 
 impl From<ConfigNumber> for Config {
@@ -404,6 +450,9 @@ pub enum MalformedPacketError {
     ///
     /// [RFC 6716 § 3.4:R5]: https://tools.ietf.org/html/rfc6716#ref-R5
     OverlongDuration,
+    /// A code 3 packet declared more frames than [`Packet::new_bounded`]'s caller-supplied
+    /// ceiling allowed, or allocating storage for its frames failed.
+    AllocationFailed,
 }
 
 impl Display for MalformedPacketError {
@@ -414,6 +463,9 @@ impl Display for MalformedPacketError {
             MalformedPacketError::FrameOverflow => "contained frame longer than packet itself",
             MalformedPacketError::ZeroFrames => "contained zero frames",
             MalformedPacketError::OverlongDuration => "frames totaled longer than 120 ms",
+            MalformedPacketError::AllocationFailed => {
+                "frame count exceeded the allowed bound, or allocating frame storage failed"
+            }
         })
     }
 }
@@ -437,7 +489,7 @@ type DecodeFunction<'a> = fn(Config, bool, bool, &'a [u8]) -> Result<(Packet<'a>
 
 impl<'a> Packet<'a> {
     /// The maximum allowable duration of a packet in microseconds.
-    const DURATION_MAX: u32 = 120_000;
+    pub(crate) const DURATION_MAX: u32 = 120_000;
 
     /// The maximum implicit length of a frame, in bytes, according to RFC 6716 § 3.4:R2
     const FRAME_LEN_MAX: usize = 1275;
@@ -455,6 +507,32 @@ impl<'a> Packet<'a> {
         }
     }
 
+    /// Encodes `len` as a length code (RFC 6716 § 3.2.1), the inverse of
+    /// [`Packet::length_code`]. Used by the [`Repacketizer`](crate::repacketizer::Repacketizer)
+    /// to serialize merged packets.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MalformedPacketError::OverlongFrame`] if `len` exceeds [`Packet::FRAME_LEN_MAX`].
+    pub(crate) fn encode_length(len: usize) -> Result<Vec<u8>> {
+        match len {
+            0..=251 => Ok(vec![len as u8]),
+            252..=Packet::FRAME_LEN_MAX => {
+                let val = len - 252;
+                Ok(vec![(252 + (val % 4)) as u8, (val / 4) as u8])
+            }
+            _ => Err(MalformedPacketError::OverlongFrame.into()),
+        }
+    }
+
+    /// Builds the single TOC byte for a packet with the given configuration, stereo flag, and
+    /// frame-count code `c` (0–3), the inverse of [`TableOfContents`]'s accessors. Used by the
+    /// [`Repacketizer`](crate::repacketizer::Repacketizer) and multistream splitting.
+    pub(crate) fn toc_byte(config: Config, stereo: bool, c: u8) -> u8 {
+        debug_assert!(c <= 3);
+        (config.to_config_number() << 3) | (u8::from(stereo) << 2) | c
+    }
+
     /// Returns data necessary for self-delimiting framing, or the default data if not using
     /// self-delimiting framing.
     fn framing<T>(data: &[u8], self_delimited: bool, implicit: T) -> Result<(usize, usize, &[u8])>
@@ -614,34 +692,40 @@ impl<'a> Packet<'a> {
     ) -> Result<(Packet<'a>, &'a [u8])> {
         let mut offset = 0;
 
+        let lens = (0..frame_count)
+            .scan(0, |total_len, i| {
+                Some(if self_delimiting || i < frame_count - 1 {
+                    match Packet::length_code(&data[offset..]) {
+                        Ok((len, lc_size)) => {
+                            offset += lc_size;
+                            *total_len += len;
+                            Ok(len)
+                        }
+                        Err(err) => Err(err),
+                    }
+                } else {
+                    Ok(data.len() - *total_len - offset - padding)
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // Fallibly reserve storage for the frame slices up front, rather than growing the `Vec`
+        // as frames are collected, so a hostile frame count can't abort the process on OOM.
+        let mut frames = Vec::new();
+        frames
+            .try_reserve_exact(lens.len())
+            .map_err(|_| MalformedPacketError::AllocationFailed)?;
+        for len in lens {
+            let new_offset = offset + len;
+            frames.push(data.get_res(offset..new_offset)?);
+            offset = new_offset;
+        }
+
         Ok((
             Packet {
                 config,
                 stereo,
-                frames: (0..frame_count)
-                    .scan(0, |total_len, i| {
-                        Some(if self_delimiting || i < frame_count - 1 {
-                            match Packet::length_code(&data[offset..]) {
-                                Ok((len, lc_size)) => {
-                                    offset += lc_size;
-                                    *total_len += len;
-                                    Ok(len)
-                                }
-                                Err(err) => Err(err),
-                            }
-                        } else {
-                            Ok(data.len() - *total_len - offset - padding)
-                        })
-                    })
-                    .collect::<Result<Vec<_>>>()?
-                    .into_iter()
-                    .map(|len| {
-                        let new_offset = offset + len;
-                        let data = data.get_res(offset..new_offset)?;
-                        offset = new_offset;
-                        Ok(data)
-                    })
-                    .collect::<Result<Vec<_>>>()?,
+                frames,
             },
             &data.get_res(offset + padding..)?,
         ))
@@ -666,13 +750,22 @@ impl<'a> Packet<'a> {
         };
 
         let data = &data[offset..];
+
+        // Fallibly reserve storage for the frame slices up front, rather than growing the `Vec`
+        // as frames are collected, so a hostile frame count can't abort the process on OOM.
+        let mut frames = Vec::new();
+        frames
+            .try_reserve_exact(frame_count)
+            .map_err(|_| MalformedPacketError::AllocationFailed)?;
+        for i in 0..frame_count {
+            frames.push(data.get_res(len * i..len * (i + 1))?);
+        }
+
         Ok((
             Packet {
                 config,
                 stereo,
-                frames: (0..frame_count)
-                    .map(|i| Ok(data.get_res(len * i..len * (i + 1))?))
-                    .collect::<Result<Vec<_>>>()?,
+                frames,
             },
             &data.get_res(len * frame_count + padding..)?,
         ))
@@ -696,6 +789,32 @@ impl<'a> Packet<'a> {
         Self::new_with_framing(data, false).map(|(packet, _)| packet)
     }
 
+    /// Decodes a packet from bytes like [`Packet::new`], but rejects a code 3 packet up front
+    /// if it declares more than `max_frames` frames.
+    ///
+    /// `Packet::new` already allocates frame storage fallibly (see [`Vec::try_reserve_exact`]),
+    /// so it cannot abort the process on OOM; `new_bounded` additionally lets a caller
+    /// processing attacker-controlled network packets impose a tighter, application-specific
+    /// ceiling than the protocol's own (120&nbsp;ms / frame size) limit, without decoding any
+    /// further into the packet first.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MalformedPacketError::AllocationFailed`] if the packet declares more than
+    /// `max_frames` frames.
+    pub fn new_bounded(data: &'a [u8], max_frames: usize) -> Result<Packet<'a>> {
+        let toc = TableOfContents::from(*data.first_res()?);
+
+        if toc.frames_layout() == FramesLayout::Arbitrary {
+            let frame_count = usize::from(FrameCount::from(*data.get_res(1)?).frame_count());
+            if frame_count > max_frames {
+                return Err(MalformedPacketError::AllocationFailed.into());
+            }
+        }
+
+        Self::new(data)
+    }
+
     /// Decodes a potentially self-delimited packet from bytes.
     ///
     /// See [RFC 6716 Appendix B].
@@ -713,6 +832,83 @@ impl<'a> Packet<'a> {
             &data[1..],
         )
     }
+
+    /// Returns the number of frames contained within this packet.
+    pub fn nb_frames(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Returns this packet's configuration, for code shared with other crate modules (the
+    /// [`Repacketizer`](crate::repacketizer::Repacketizer), multistream splitting, and the like).
+    pub(crate) fn config(&self) -> Config {
+        self.config
+    }
+
+    /// Returns whether this packet's frames are encoded as stereo.
+    pub(crate) fn stereo(&self) -> bool {
+        self.stereo
+    }
+
+    /// Returns the byte slice of each individual frame contained within this packet.
+    pub(crate) fn frames(&self) -> &[&'a [u8]] {
+        &self.frames
+    }
+
+    /// Returns the number of samples (per channel) this packet will produce when decoded at
+    /// `sample_rate` Hz.
+    pub fn nb_samples(&self, sample_rate: u32) -> usize {
+        Packet::samples_per_frame(self.config.frame_size(), sample_rate) * self.nb_frames()
+    }
+
+    /// Returns the total playback duration of this packet's frames.
+    pub fn duration(&self) -> Duration {
+        Duration::from(self.config.frame_size()) * self.nb_frames() as u32
+    }
+
+    /// Returns the bandwidth shared by every frame in this packet.
+    pub fn bandwidth(&self) -> Bandwidth {
+        self.config.bandwidth()
+    }
+
+    /// Returns the number of channels (1 for mono, 2 for stereo) this packet decodes to.
+    pub fn channel_count(&self) -> u8 {
+        if self.stereo {
+            2
+        } else {
+            1
+        }
+    }
+
+    /// Returns the duration of each individual frame in this packet.
+    pub fn frame_size(&self) -> FrameSize {
+        self.config.frame_size()
+    }
+
+    /// Returns the number of samples a single frame of `frame_size` decodes to at `sample_rate`
+    /// Hz.
+    fn samples_per_frame(frame_size: FrameSize, sample_rate: u32) -> usize {
+        let frame_us = u64::from(frame_size.as_microseconds());
+        ((frame_us * u64::from(sample_rate)) / 1_000_000) as usize
+    }
+
+    /// Returns the number of samples (per channel) a packet will produce at `sample_rate` Hz,
+    /// parsing only its Table-of-Contents and frame-count bytes.
+    ///
+    /// Unlike [`Packet::new`], this never materializes the individual frame slices, so a
+    /// streaming consumer can size an exact output buffer from just the first few bytes of a
+    /// packet.
+    pub fn nb_samples_from_toc(data: &[u8], sample_rate: u32) -> Result<usize> {
+        let toc = TableOfContents::from(*data.first_res()?);
+        let frame_count = match toc.frames_layout() {
+            FramesLayout::One => 1,
+            FramesLayout::TwoEqual | FramesLayout::TwoDifferent => 2,
+            FramesLayout::Arbitrary => {
+                usize::from(FrameCount::from(*data.get_res(1)?).frame_count())
+            }
+        };
+
+        Ok(Packet::samples_per_frame(toc.frame_size(), sample_rate) * frame_count)
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -721,6 +917,11 @@ pub struct Decoder {
     channels: u8,
     silk: SilkDecoder,
     celt: CeltDecoder,
+    /// The [`Config`] of the most recently decoded frame, kept so that a lost packet (a `None`
+    /// passed to [`decode`]) still has a sensible frame size and bandwidth to conceal.
+    ///
+    /// [`decode`]: Decoder::decode
+    last_config: Option<Config>,
 }
 
 impl Decoder {
@@ -730,6 +931,7 @@ impl Decoder {
             channels,
             silk: SilkDecoder,
             celt: CeltDecoder,
+            last_config: None,
         }
     }
 
@@ -751,20 +953,371 @@ impl Decoder {
         unimplemented!()
     }
 
+    /// Synthesizes one frame of packet-loss concealment audio, continuing the SILK and/or CELT
+    /// decoder state left behind by the most recently decoded frame.
+    ///
+    /// A lost packet with no prior successfully decoded frame has nothing to extrapolate from,
+    /// so the default [`Config`] (config number 0: SILK narrowband, 10 ms) is assumed as a last
+    /// resort.
+    fn decode_loss<S, T>(&mut self, buf: &mut S) -> Result<usize>
+    where
+        S: Samples<T>,
+        T: Sample,
+    {
+        let config = self.last_config.unwrap_or_default();
+
+        match config.mode() {
+            Mode::Silk => self.silk.conceal(config.frame_size(), self.channels, buf),
+            Mode::Hybrid => self.decode_loss_hybrid(config, buf),
+            Mode::Celt => self
+                .celt
+                .conceal(config.frame_size(), config.bandwidth(), self.channels, buf),
+        }
+    }
+
+    /// Synthesizes Hybrid-mode concealment by decoding SILK's low band and CELT's high band
+    /// into separate scratch buffers, then mixing them sample-by-sample into `buf`.
+    ///
+    /// [`Samples::write_frame`](crate::sample::Samples::write_frame) is a full overwrite at
+    /// each index, not additive, so decoding both sub-modes straight into `buf` would let CELT
+    /// clobber every sample SILK had just written instead of the two bands combining—exactly as
+    /// real Hybrid decode must combine them.
+    fn decode_loss_hybrid<S, T>(&mut self, config: Config, buf: &mut S) -> Result<usize>
+    where
+        S: Samples<T>,
+        T: Sample,
+    {
+        let channels = usize::from(self.channels);
+
+        let mut silk_buf: Vec<T> = Vec::new();
+        let silk_samples = self.silk.conceal(config.frame_size(), self.channels, &mut silk_buf)?;
+
+        let mut celt_buf: Vec<T> = Vec::new();
+        let celt_samples =
+            self.celt
+                .conceal(config.frame_size(), config.bandwidth(), self.channels, &mut celt_buf)?;
+
+        Ok(mix_hybrid_concealment(
+            &silk_buf,
+            silk_samples,
+            &celt_buf,
+            celt_samples,
+            channels,
+            buf,
+        ))
+    }
+
+    /// Recovers a lost frame from the in-band Forward Error Correction (LBRR) data carried
+    /// inside the packet that followed it.
+    ///
+    /// `next_packet` is the packet received immediately after the lost one, and
+    /// `lost_frame_size` is the frame size the lost frame is assumed to have had, since no TOC
+    /// byte survived for it. Only `next_packet`'s first frame is consulted, matching how LBRR
+    /// redundancy only ever covers the single frame immediately prior.
+    ///
+    /// SILK and hybrid packets open with a per-frame LBRR flag ahead of the primary frame data;
+    /// when set, a coarsely quantized copy of the previous frame is decoded from it into `buf`,
+    /// resampling to `lost_frame_size` as needed. CELT carries no such redundancy, and a missing
+    /// LBRR flag falls back to the ordinary concealment used by [`decode`].
+    ///
+    /// [`decode`]: Decoder::decode
+    pub fn decode_fec<S, T>(
+        &mut self,
+        next_packet: &Packet<'_>,
+        lost_frame_size: FrameSize,
+        buf: &mut S,
+    ) -> Result<usize>
+    where
+        S: Samples<T>,
+        T: Sample,
+    {
+        use crate::ec::RangeDecoder;
+
+        if next_packet.config.mode() == Mode::Celt {
+            return self.decode_loss(buf);
+        }
+
+        let frame = next_packet
+            .frames
+            .first()
+            .ok_or(MalformedPacketError::ZeroFrames)?;
+        let mut ec_dec = RangeDecoder::new(frame);
+
+        if self.silk.has_lbrr(&mut ec_dec, next_packet.stereo)? {
+            self.silk.decode_lbrr(
+                &mut ec_dec,
+                lost_frame_size,
+                next_packet.stereo,
+                self.channels,
+                buf,
+            )
+        } else {
+            self.decode_loss(buf)
+        }
+    }
+
     pub fn decode<'a, S, T>(&mut self, packet: Option<Packet<'a>>, buf: &mut S) -> Result<usize>
     where
         S: Samples<T>,
         T: Sample,
     {
         if let Some(packet) = packet {
-            packet
+            let samples = packet
                 .frames
                 .iter()
                 .map(|frame| self.decode_frame(packet.config, packet.stereo, frame, buf))
-                .sum()
+                .sum::<Result<usize>>()?;
+            self.last_config = Some(packet.config);
+            Ok(samples)
+        } else {
+            self.decode_loss(buf)
+        }
+    }
+}
+
+/// Mixes `silk_buf` and `celt_buf`—each an interleaved, `channels`-wide scratch buffer holding
+/// one sub-decoder's independent contribution to the same Hybrid frame—sample-by-sample into
+/// `buf`, writing `min(silk_samples, celt_samples)` frames.
+///
+/// Kept as a free function, separate from the scratch-buffer setup in
+/// [`Decoder::decode_loss_hybrid`], so the actual merge arithmetic is testable without a real
+/// `SilkDecoder`/`CeltDecoder` to drive it.
+fn mix_hybrid_concealment<S, T>(
+    silk_buf: &[T],
+    silk_samples: usize,
+    celt_buf: &[T],
+    celt_samples: usize,
+    channels: usize,
+    buf: &mut S,
+) -> usize
+where
+    S: Samples<T>,
+    T: Sample,
+{
+    let samples = silk_samples.min(celt_samples);
+    let mut frame = vec![T::default(); channels];
+
+    for index in 0..samples {
+        for (channel, sample) in frame.iter_mut().enumerate() {
+            let silk_sample = silk_buf[index * channels + channel];
+            let celt_sample = celt_buf[index * channels + channel];
+            *sample = silk_sample.mix(celt_sample);
+        }
+        buf.write_frame(index, &frame);
+    }
+
+    samples
+}
+
+/// The error type returned when a multistream packet's sub-streams can't be merged into one
+/// output.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum MultistreamError {
+    /// Two sub-streams of the same packet decoded to different sample counts. A well-formed
+    /// encoder never produces this, so seeing it means the packet is malformed or adversarial.
+    MismatchedStreamLength,
+}
+
+impl Display for MultistreamError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            MultistreamError::MismatchedStreamLength => {
+                "multistream packet's sub-streams decoded to different sample counts"
+            }
+        })
+    }
+}
+
+impl error::Error for MultistreamError {}
+
+/// Decodes a multistream (surround) Opus bitstream, as defined for the Ogg and WebM
+/// encapsulations ([RFC 7845 § 5.1.1]) for channel mapping families 0, 1, and 255.
+///
+/// A multistream packet bundles `N` independent Opus packets, self-delimited (see
+/// [`Packet::new_with_framing`]) except for the last. The first `M` streams are *coupled* and
+/// decode to a stereo pair each; the remaining `N - M` streams are mono. This produces
+/// `M * 2 + (N - M)` internal channels, which a [`MappingTable`] routes to the final output
+/// channels—or silences, for channels mapped to `255`.
+///
+/// This type decodes a standalone multistream bitstream; it is not currently used by
+/// [`OggOpusReader`](crate::ogg::OggOpusReader) or
+/// [`OggOpusStream`](crate::ogg::OggOpusStream), which decode Ogg-encapsulated multistream
+/// packets through [`multipacket::Multipacket`](crate::multipacket::Multipacket) instead.
+///
+/// [RFC 7845 § 5.1.1]: https://tools.ietf.org/html/rfc7845#section-5.1.1
+#[derive(Debug, Clone)]
+pub struct MultistreamDecoder {
+    /// One [`Decoder`] per stream; the first `coupled_streams` are stereo, the rest mono.
+    decoders: Vec<Decoder>,
+    coupled_streams: u8,
+    mapping: MappingTable,
+}
+
+impl MultistreamDecoder {
+    /// Creates a decoder for `streams` total Opus streams, the first `coupled_streams` of which
+    /// are coupled (stereo), routing the `coupled_streams * 2 + (streams - coupled_streams)`
+    /// resulting internal channels to output channels per `mapping`.
+    pub fn new(
+        sample_rate: u32,
+        streams: u8,
+        coupled_streams: u8,
+        mapping: MappingTable,
+    ) -> MultistreamDecoder {
+        let decoders = (0..streams)
+            .map(|i| {
+                let channels = if i < coupled_streams { 2 } else { 1 };
+                Decoder::new(sample_rate, channels)
+            })
+            .collect();
+
+        MultistreamDecoder {
+            decoders,
+            coupled_streams,
+            mapping,
+        }
+    }
+
+    /// Splits a multistream packet's bytes into each contained stream's [`Packet`], using
+    /// self-delimiting framing ([RFC 6716 Appendix B]) for every stream but the last.
+    ///
+    /// [RFC 6716 Appendix B]: https://tools.ietf.org/html/rfc6716#appendix-B
+    fn split<'a>(&self, data: &'a [u8]) -> Result<Vec<Packet<'a>>> {
+        let mut packets = Vec::with_capacity(self.decoders.len());
+        let mut data = data;
+
+        for i in 0..self.decoders.len() {
+            let self_delimited = i < self.decoders.len() - 1;
+            let (packet, rest) = Packet::new_with_framing(data, self_delimited)?;
+            packets.push(packet);
+            data = rest;
+        }
+
+        Ok(packets)
+    }
+
+    /// Returns the sample drawn from internal channel `internal_channel` of `stream_bufs` at
+    /// `frame_index`, where `stream_bufs` holds one interleaved per-stream buffer per decoder.
+    fn internal_sample<T: Sample>(
+        stream_bufs: &[Vec<T>],
+        coupled_streams: u8,
+        internal_channel: u8,
+        frame_index: usize,
+    ) -> T {
+        let coupled = usize::from(coupled_streams);
+        let internal_channel = usize::from(internal_channel);
+
+        if internal_channel < coupled * 2 {
+            stream_bufs[internal_channel / 2][frame_index * 2 + internal_channel % 2]
         } else {
-            // TODO packet loss concealment
-            unimplemented!()
+            stream_bufs[coupled + (internal_channel - coupled * 2)][frame_index]
+        }
+    }
+
+    /// Records `stream_samples` as the sample count every stream decoded to so far, or returns
+    /// [`MultistreamError::MismatchedStreamLength`] if it disagrees with an earlier stream's.
+    ///
+    /// A malformed or adversarial multistream packet isn't required to keep every sub-packet's
+    /// payload in sync, so without this check a sub-stream that decodes to fewer samples than
+    /// an earlier one would leave [`internal_sample`](Self::internal_sample) indexing past the
+    /// end of its buffer.
+    fn check_stream_samples(samples: &mut Option<usize>, stream_samples: usize) -> Result<()> {
+        match *samples {
+            None => *samples = Some(stream_samples),
+            Some(expected) if expected != stream_samples => {
+                return Err(MultistreamError::MismatchedStreamLength.into());
+            }
+            Some(_) => {}
+        }
+        Ok(())
+    }
+
+    /// Decodes one multistream packet, or conceals a lost one (`data` is `None`), routing each
+    /// stream's output channels into `buf` according to the channel-mapping table.
+    pub fn decode<S, T>(&mut self, data: Option<&[u8]>, buf: &mut S) -> Result<usize>
+    where
+        S: Samples<T>,
+        T: Sample,
+    {
+        let mut stream_bufs: Vec<Vec<T>> = vec![Vec::new(); self.decoders.len()];
+
+        let samples = match data {
+            Some(data) => {
+                let sub_packets = self.split(data)?;
+                let mut samples = None;
+                for ((decoder, sub_packet), stream_buf) in self
+                    .decoders
+                    .iter_mut()
+                    .zip(sub_packets)
+                    .zip(&mut stream_bufs)
+                {
+                    let stream_samples = decoder.decode(Some(sub_packet), stream_buf)?;
+                    Self::check_stream_samples(&mut samples, stream_samples)?;
+                }
+                samples.unwrap_or(0)
+            }
+            None => {
+                let mut samples = None;
+                for (decoder, stream_buf) in self.decoders.iter_mut().zip(&mut stream_bufs) {
+                    let stream_samples = decoder.decode(None, stream_buf)?;
+                    Self::check_stream_samples(&mut samples, stream_samples)?;
+                }
+                samples.unwrap_or(0)
+            }
+        };
+
+        let output_channels = usize::from(self.mapping.channels());
+        let mut frame = vec![T::default(); output_channels];
+
+        for index in 0..samples {
+            for (out_channel, sample) in frame.iter_mut().enumerate() {
+                let internal_channel = self.mapping.channel(out_channel as u8);
+                *sample = if internal_channel == MappingTable::SILENT_CHANNEL {
+                    T::default()
+                } else {
+                    Self::internal_sample(
+                        &stream_bufs,
+                        self.coupled_streams,
+                        internal_channel,
+                        index,
+                    )
+                };
+            }
+            buf.write_frame(index, &frame);
         }
+
+        Ok(samples)
+    }
+}
+
+#[cfg(test)]
+mod hybrid_concealment_tests {
+    use super::*;
+
+    #[test]
+    fn mixes_both_sub_decoders_instead_of_letting_one_overwrite_the_other() {
+        let silk_buf = [1.0f32, 2.0, 3.0, 4.0]; // two stereo frames
+        let celt_buf = [10.0f32, 20.0, 30.0, 40.0];
+
+        let mut buf: Vec<f32> = Vec::new();
+        let samples = mix_hybrid_concealment(&silk_buf, 2, &celt_buf, 2, 2, &mut buf);
+
+        assert_eq!(samples, 2);
+        assert_eq!(buf, vec![11.0, 22.0, 33.0, 44.0]);
+
+        // Regression guard: the bug this replaces overwrote SILK's contribution with CELT's,
+        // so the result was indistinguishable from CELT's buffer alone.
+        assert_ne!(buf, celt_buf.to_vec());
+    }
+
+    #[test]
+    fn stops_at_the_shorter_of_the_two_sub_decoders_sample_counts() {
+        let silk_buf = [1i16, 1, 1, 1, 1, 1]; // 3 mono frames
+        let celt_buf = [2i16, 2, 2, 2, 2, 2];
+
+        let mut buf: Vec<i16> = Vec::new();
+        let samples = mix_hybrid_concealment(&silk_buf, 3, &celt_buf, 2, 1, &mut buf);
+
+        assert_eq!(samples, 2);
+        assert_eq!(buf, vec![3, 3]);
     }
 }
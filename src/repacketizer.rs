@@ -0,0 +1,213 @@
+//! Merging and splitting of already-parsed Opus packets ([RFC 6716 § 3.2]).
+//!
+//! [RFC 6716 § 3.2]: https://tools.ietf.org/html/rfc6716#section-3.2
+
+use crate::{
+    error::Result,
+    packet::{Config, Packet},
+};
+use std::{
+    error,
+    fmt::{self, Display, Formatter},
+};
+
+/// The error type returned when frames cannot be repacketized together.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum RepacketizeError {
+    /// A frame or packet was added to a [`Repacketizer`] whose configuration or channel count
+    /// didn't match the frames already added to it; every frame merged into one packet must
+    /// share a single TOC byte.
+    MismatchedConfig,
+    /// The repacketized output would have contained zero frames.
+    NoFrames,
+}
+
+impl Display for RepacketizeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            RepacketizeError::MismatchedConfig => {
+                "frame configuration or channel count did not match the rest of the packet"
+            }
+            RepacketizeError::NoFrames => "no frames were added to repacketize",
+        })
+    }
+}
+
+impl error::Error for RepacketizeError {}
+
+/// Merges frames sharing an identical [`Config`] and channel count into a single, tightly
+/// packed Opus packet.
+///
+/// Frames are added with [`add_packet`]/[`add_frame`], then serialized with [`out`] using
+/// whichever packet code (0–3, see [RFC 6716 § 3.2]) is the smallest fit: a single frame is
+/// code 0, two equal-length frames are code 1, two different-length frames are code 2, and
+/// anything else is code 3—CBR if every frame is the same length, VBR otherwise.
+///
+/// This is the inverse of [`split`], and is useful for a gateway that needs to rebundle Opus
+/// frames to fit a different network MTU than they originally arrived in.
+///
+/// [`add_packet`]: Repacketizer::add_packet
+/// [`add_frame`]: Repacketizer::add_frame
+/// [`out`]: Repacketizer::out
+/// [RFC 6716 § 3.2]: https://tools.ietf.org/html/rfc6716#section-3.2
+#[derive(Debug, Clone)]
+pub struct Repacketizer<'a> {
+    config: Option<Config>,
+    stereo: bool,
+    frames: Vec<&'a [u8]>,
+}
+
+impl<'a> Default for Repacketizer<'a> {
+    fn default() -> Self {
+        Repacketizer {
+            config: None,
+            stereo: false,
+            frames: Vec::new(),
+        }
+    }
+}
+
+impl<'a> Repacketizer<'a> {
+    /// Creates an empty repacketizer.
+    pub fn new() -> Self {
+        Repacketizer::default()
+    }
+
+    /// Checks that `config`/`stereo` match whatever has already been added, recording them if
+    /// this is the first frame.
+    fn check_config(&mut self, config: Config, stereo: bool) -> Result<()> {
+        match self.config {
+            Some(c) if c == config && self.stereo == stereo => Ok(()),
+            Some(_) => Err(RepacketizeError::MismatchedConfig.into()),
+            None => {
+                self.config = Some(config);
+                self.stereo = stereo;
+                Ok(())
+            }
+        }
+    }
+
+    /// Appends every frame of `packet` onto the packet under construction.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RepacketizeError::MismatchedConfig`] if `packet`'s configuration or channel
+    /// count differs from frames already added since the last [`reset`](Repacketizer::reset).
+    pub fn add_packet(&mut self, packet: &Packet<'a>) -> Result<()> {
+        self.check_config(packet.config(), packet.stereo())?;
+        self.frames.extend_from_slice(packet.frames());
+        Ok(())
+    }
+
+    /// Appends a single raw frame, sharing `config`/`stereo` with any frames already added.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RepacketizeError::MismatchedConfig`] if `config`/`stereo` differ from frames
+    /// already added since the last [`reset`](Repacketizer::reset).
+    pub fn add_frame(&mut self, config: Config, stereo: bool, frame: &'a [u8]) -> Result<()> {
+        self.check_config(config, stereo)?;
+        self.frames.push(frame);
+        Ok(())
+    }
+
+    /// Returns the number of frames added so far.
+    pub fn nb_frames(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Clears all frames added so far, so the `Repacketizer` can be reused to build a new
+    /// output packet.
+    pub fn reset(&mut self) {
+        self.config = None;
+        self.stereo = false;
+        self.frames.clear();
+    }
+
+    /// Serializes every frame added so far into a single Opus packet.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RepacketizeError::NoFrames`] if no frames have been added, or a
+    /// [`MalformedPacketError`](crate::packet::MalformedPacketError) if the result would
+    /// violate one of the packet invariants the decoder itself enforces (more than 1275 bytes
+    /// in a single frame, more than 120 ms total).
+    pub fn out(&self) -> Result<Vec<u8>> {
+        let config = self.config.ok_or(RepacketizeError::NoFrames)?;
+
+        // Codes 0 and 1, CBR code 3, and the last frame of codes 2 and VBR 3 carry no length
+        // code, so `Packet::encode_length` alone wouldn't catch an overlong frame there.
+        if self.frames.iter().any(|frame| frame.len() > 1275) {
+            return Err(crate::packet::MalformedPacketError::OverlongFrame.into());
+        }
+
+        match &self.frames[..] {
+            [] => Err(RepacketizeError::NoFrames.into()),
+            [frame] => {
+                let mut out = vec![Packet::toc_byte(config, self.stereo, 0)];
+                out.extend_from_slice(frame);
+                Ok(out)
+            }
+            [a, b] if a.len() == b.len() => {
+                let mut out = vec![Packet::toc_byte(config, self.stereo, 1)];
+                out.extend_from_slice(a);
+                out.extend_from_slice(b);
+                Ok(out)
+            }
+            [a, b] => {
+                let mut out = vec![Packet::toc_byte(config, self.stereo, 2)];
+                out.extend(Packet::encode_length(a.len())?);
+                out.extend_from_slice(a);
+                out.extend_from_slice(b);
+                Ok(out)
+            }
+            frames => self.out_code_3(config, frames),
+        }
+    }
+
+    /// Serializes `frames` (3 or more of them) as a code 3 packet, CBR if every frame is the
+    /// same length and VBR otherwise.
+    fn out_code_3(&self, config: Config, frames: &[&'a [u8]]) -> Result<Vec<u8>> {
+        use crate::packet::MalformedPacketError;
+
+        // Mirrors `Packet::decode_code_3`'s own R5 check: the 120 ms cap is on total
+        // duration, not frame count, so a flat frame-count limit would only be correct for
+        // 2.5 ms frames and would silently let larger frame sizes build an overlong packet.
+        let length_us = u32::from(config.frame_size().as_microseconds());
+        if frames.len() as u32 * length_us > Packet::DURATION_MAX {
+            return Err(MalformedPacketError::OverlongDuration.into());
+        }
+
+        let cbr = frames.windows(2).all(|w| w[0].len() == w[1].len());
+        let frame_count_byte = (u8::from(!cbr) << 7) | (frames.len() as u8);
+
+        let mut out = vec![Packet::toc_byte(config, self.stereo, 3), frame_count_byte];
+
+        if !cbr {
+            for frame in &frames[..frames.len() - 1] {
+                out.extend(Packet::encode_length(frame.len())?);
+            }
+        }
+
+        for frame in frames {
+            out.extend_from_slice(frame);
+        }
+
+        Ok(out)
+    }
+}
+
+/// Splits a multi-frame `packet` into one single-frame, code-0 packet per contained frame.
+///
+/// This is the inverse of merging frames with a [`Repacketizer`].
+pub fn split(packet: &Packet<'_>) -> Vec<Vec<u8>> {
+    packet
+        .frames()
+        .iter()
+        .map(|frame| {
+            let mut out = vec![Packet::toc_byte(packet.config(), packet.stereo(), 0)];
+            out.extend_from_slice(frame);
+            out
+        })
+        .collect()
+}
@@ -0,0 +1,125 @@
+//! Depayloading of RTP-transported Opus streams ([RFC 7587]).
+//!
+//! [RFC 7587]: https://tools.ietf.org/html/rfc7587
+
+use crate::{error::Result, packet::Packet};
+
+/// The subset of an RTP packet header ([RFC 3550 § 5.1]) the Opus depayloader needs: the
+/// sequence number and timestamp used to detect a gap in the stream.
+///
+/// [RFC 3550 § 5.1]: https://tools.ietf.org/html/rfc3550#section-5.1
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RtpHeader {
+    /// Identifies a packet's position in the sequence, incrementing by one per RTP packet.
+    pub sequence_number: u16,
+    /// The sampling instant of this packet's first sample, in the stream's clock rate.
+    pub timestamp: u32,
+}
+
+/// The sequence number and timestamp a [`Depayloader`] expects of the next RTP packet.
+#[derive(Debug, Clone, Copy)]
+struct Expected {
+    sequence_number: u16,
+    timestamp: u32,
+}
+
+/// The largest gap, in concealment frames, that [`Depayloader::push`] will materialize as
+/// individual [`Frame::Lost`] entries.
+///
+/// An RTP timestamp delta is attacker-controllable (a single forward sequence-number step,
+/// trivially passing the reorder check, can carry an arbitrary `u32` timestamp jump), so
+/// converting it directly into that many `Vec` entries would let one [`push`] call demand an
+/// unbounded allocation. Beyond this many frames, the gap is reported as a single
+/// [`Frame::Gap`] instead.
+///
+/// [`push`]: Depayloader::push
+const MAX_CONCEALED_FRAMES: u32 = 256;
+
+/// One entry of a [`Depayloader::push`] result.
+#[derive(Debug, Clone)]
+pub enum Frame<'a> {
+    /// A single frame lost to the network; feed this to the decoder's packet-loss
+    /// concealment path (e.g. [`Decoder::decode`] with no packet).
+    ///
+    /// [`Decoder::decode`]: crate::packet::Decoder::decode
+    Lost,
+    /// A gap larger than [`MAX_CONCEALED_FRAMES`], reported once rather than as that many
+    /// individual [`Frame::Lost`] entries. The caller should treat this as a stream
+    /// discontinuity—e.g. resetting decoder state—rather than frame-accurate concealment.
+    Gap,
+    /// A depayloaded Opus packet.
+    Packet(Packet<'a>),
+}
+
+/// Turns RTP packets carrying Opus payloads ([RFC 7587]) into this crate's [`Packet`]s.
+///
+/// Per [RFC 7587 § 4], each RTP payload is exactly one Opus packet, so depayloading itself is
+/// trivial ([`Packet::new`]). The useful work this type does is tracking sequence numbers and
+/// timestamps to detect loss: a discontinuity in [`push`]'s input yields synthetic
+/// [`Frame::Lost`] markers—one per concealment frame the gap represents, computed from the
+/// RTP timestamp delta—so the consumer can drive [`Decoder::decode`]'s existing concealment
+/// path without waiting for a retransmission. A gap too large to enumerate safely is reported
+/// as a single [`Frame::Gap`] instead; see [`MAX_CONCEALED_FRAMES`].
+///
+/// [RFC 7587]: https://tools.ietf.org/html/rfc7587
+/// [RFC 7587 § 4]: https://tools.ietf.org/html/rfc7587#section-4
+/// [`push`]: Depayloader::push
+/// [`Decoder::decode`]: crate::packet::Decoder::decode
+#[derive(Debug, Clone)]
+pub struct Depayloader {
+    /// The clock rate of the RTP stream, in Hz—always 48000 for Opus per [RFC 7587 § 4.1].
+    ///
+    /// [RFC 7587 § 4.1]: https://tools.ietf.org/html/rfc7587#section-4.1
+    clock_rate: u32,
+    /// What the next pushed RTP packet is expected to carry, or `None` before the first packet.
+    next: Option<Expected>,
+}
+
+impl Depayloader {
+    /// Creates a depayloader for a stream clocked at `clock_rate` Hz.
+    pub fn new(clock_rate: u32) -> Depayloader {
+        Depayloader {
+            clock_rate,
+            next: None,
+        }
+    }
+
+    /// Feeds one RTP packet's header and payload, returning the Opus packets it represents:
+    /// loss markers for any detected gap (see [`Frame`]), followed by the depayloaded packet
+    /// itself.
+    pub fn push<'a>(&mut self, header: RtpHeader, payload: &'a [u8]) -> Result<Vec<Frame<'a>>> {
+        let packet = Packet::new(payload)?;
+        let packet_samples = packet.nb_samples(self.clock_rate) as u32;
+        let mut out = Vec::new();
+
+        if let Some(expected) = self.next {
+            // A wrapping comparison: a sequence number "ahead" of `expected` by half the u16
+            // range or more—i.e. really behind it—is treated as a duplicate/reordered packet,
+            // not a gap, and is passed through rather than preceded by spurious loss markers.
+            let seq_gap = header.sequence_number.wrapping_sub(expected.sequence_number);
+            if seq_gap != 0 && seq_gap < u16::MAX / 2 {
+                let ts_gap = header.timestamp.wrapping_sub(expected.timestamp);
+                let frame_samples = packet_samples / packet.nb_frames().max(1) as u32;
+                let lost_frames = if frame_samples == 0 {
+                    0
+                } else {
+                    ts_gap / frame_samples
+                };
+
+                if lost_frames > MAX_CONCEALED_FRAMES {
+                    out.push(Frame::Gap);
+                } else {
+                    out.extend(std::iter::repeat(Frame::Lost).take(lost_frames as usize));
+                }
+            }
+        }
+
+        self.next = Some(Expected {
+            sequence_number: header.sequence_number.wrapping_add(1),
+            timestamp: header.timestamp.wrapping_add(packet_samples),
+        });
+
+        out.push(Frame::Packet(packet));
+        Ok(out)
+    }
+}
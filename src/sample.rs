@@ -1,9 +1,146 @@
-pub trait Sample {}
+//! Frame-oriented, channel-aware output buffers for decoded PCM audio.
 
-impl Sample for f32 {}
-impl Sample for i16 {}
+use std::slice;
 
-pub trait Samples<T: Sample> {}
+/// A PCM sample format this crate can decode into: `i16` for fixed-point PCM, `f32` for
+/// floating-point PCM.
+pub trait Sample: Copy + Default {
+    /// Combines two samples representing simultaneous, non-overlapping contributions to the
+    /// same instant—e.g. Hybrid mode's SILK low band and CELT high band—the way two such
+    /// signals combine in a real decoder: by linear superposition.
+    fn mix(self, other: Self) -> Self;
+}
 
-impl<T> Samples<T> for [T] where T: Sample {}
-impl<T> Samples<T> for Vec<T> where T: Sample {}
\ No newline at end of file
+impl Sample for f32 {
+    fn mix(self, other: Self) -> Self {
+        self + other
+    }
+}
+
+impl Sample for i16 {
+    fn mix(self, other: Self) -> Self {
+        self.saturating_add(other)
+    }
+}
+
+/// A single instant of `N`-channel audio—one sample per channel.
+///
+/// `Frame<T, N>` has the same layout as `[T; N]`, so a slice of frames can be reinterpreted as
+/// a flat, interleaved sample buffer without copying; see [`Frame::as_interleaved`]. No
+/// conversion from a sample slice back to frames is provided.
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Frame<T, const N: usize>([T; N]);
+
+impl<T, const N: usize> Frame<T, N>
+where
+    T: Sample,
+{
+    /// Creates a new frame from its per-channel samples.
+    pub fn new(channels: [T; N]) -> Self {
+        Frame(channels)
+    }
+
+    /// Returns this frame's samples, one per channel.
+    pub fn channels(&self) -> &[T; N] {
+        &self.0
+    }
+
+    /// Reinterprets a slice of frames as a flat, interleaved slice of samples.
+    pub fn as_interleaved(frames: &[Self]) -> &[T] {
+        // SAFETY: `Frame<T, N>` is `#[repr(transparent)]` over `[T; N]`, so `frames.len()`
+        // contiguous frames and `frames.len() * N` contiguous samples share an identical
+        // layout and alignment.
+        unsafe { slice::from_raw_parts(frames.as_ptr().cast(), frames.len() * N) }
+    }
+
+    /// Reinterprets a mutable slice of frames as a flat, interleaved slice of samples.
+    pub fn as_interleaved_mut(frames: &mut [Self]) -> &mut [T] {
+        // SAFETY: see `as_interleaved`.
+        unsafe { slice::from_raw_parts_mut(frames.as_mut_ptr().cast(), frames.len() * N) }
+    }
+}
+
+impl<T, const N: usize> From<[T; N]> for Frame<T, N>
+where
+    T: Sample,
+{
+    fn from(channels: [T; N]) -> Self {
+        Frame(channels)
+    }
+}
+
+/// A sink a [`Decoder`](crate::packet::Decoder) can write decoded PCM frames into.
+///
+/// Frames are addressed by index rather than pushed sequentially, so a single decode call that
+/// produces multiple frames (e.g. a multi-frame [`Packet`](crate::packet::Packet)) can write
+/// each one directly to its final position, with no intermediate copy.
+pub trait Samples<T: Sample> {
+    /// Writes one frame's worth of samples—`frame.len()` channels—at position `index`.
+    ///
+    /// # Panics
+    ///
+    /// Implementations may panic if `index` is out of bounds for a fixed-capacity sink (see
+    /// [`capacity_frames`](Samples::capacity_frames)), or if `frame.len()` doesn't match the
+    /// sink's channel count.
+    fn write_frame(&mut self, index: usize, frame: &[T]);
+
+    /// Returns the number of whole frames this sink has room for.
+    fn capacity_frames(&self) -> usize;
+}
+
+/// An interleaved buffer (`[L R L R ...]`) that grows to fit whatever is decoded into it.
+impl<T> Samples<T> for Vec<T>
+where
+    T: Sample,
+{
+    fn write_frame(&mut self, index: usize, frame: &[T]) {
+        let start = index * frame.len();
+        let end = start + frame.len();
+
+        if self.len() < end {
+            self.resize(end, T::default());
+        }
+        self[start..end].copy_from_slice(frame);
+    }
+
+    fn capacity_frames(&self) -> usize {
+        usize::MAX
+    }
+}
+
+/// A fixed-capacity interleaved buffer (`[L R L R ...]`).
+impl<T> Samples<T> for [T]
+where
+    T: Sample,
+{
+    fn write_frame(&mut self, index: usize, frame: &[T]) {
+        let start = index * frame.len();
+        let end = start + frame.len();
+        self[start..end].copy_from_slice(frame);
+    }
+
+    fn capacity_frames(&self) -> usize {
+        self.len()
+    }
+}
+
+/// A planar buffer—one slice per channel—rather than an interleaved one.
+impl<'a, T> Samples<T> for [&'a mut [T]]
+where
+    T: Sample,
+{
+    fn write_frame(&mut self, index: usize, frame: &[T]) {
+        // Panics on a channel count mismatch rather than silently dropping the extra channels
+        // on either side.
+        assert_eq!(self.len(), frame.len(), "frame channel count mismatch");
+
+        for (channel, &sample) in self.iter_mut().zip(frame) {
+            channel[index] = sample;
+        }
+    }
+
+    fn capacity_frames(&self) -> usize {
+        self.iter().map(|channel| channel.len()).min().unwrap_or(0)
+    }
+}